@@ -0,0 +1,544 @@
+//! # Auth
+//! OAuth helpers. Most of this module is for the authorization code flow, used to act on
+//! behalf of users who log in through your own app instead of sharing their password.
+//!
+//! # Usage
+//! ```no_run
+//! use roux::Reddit;
+//! use roux::auth::{generate_state, AuthorizationCallback, Duration, Scope};
+//! # use tokio_test;
+//!
+//! # tokio_test::block_on(async {
+//! let reddit = Reddit::new("USER_AGENT", "CLIENT_ID", "CLIENT_SECRET")
+//!     .redirect_uri("https://example.com/callback");
+//!
+//! // Send the user to this url, remembering `state` for when they come back.
+//! let state = generate_state().unwrap();
+//! let url = reddit
+//!     .authorize_url(&state)
+//!     .scopes(&[Scope::Identity, Scope::Read])
+//!     .duration(Duration::Permanent)
+//!     .build()
+//!     .unwrap();
+//!
+//! // Reddit redirects the user back to the redirect uri.
+//! let callback = AuthorizationCallback::parse("https://example.com/callback?state=...&code=...");
+//! let code = callback.unwrap().verify(&state).unwrap();
+//!
+//! let me = reddit.login_code(&code).await.unwrap();
+//! # })
+//! ```
+
+use std::time::{Duration as StdDuration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::config::{Config, Grant};
+use crate::transport::Request;
+use crate::util::RouxError;
+
+/// Two-factor authentication codes.
+#[cfg(feature = "totp")]
+pub mod totp;
+
+pub mod scope;
+pub use scope::Scope;
+
+/// How long before expiry an access token is renewed.
+const RENEW_MARGIN: StdDuration = StdDuration::from_secs(60);
+
+/// How long the access granted by the user lasts.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Duration {
+    /// The token expires after an hour.
+    Temporary,
+    /// A refresh token is issued alongside the access token.
+    Permanent,
+}
+
+impl Duration {
+    /// Gets the request string for the duration
+    pub fn get_string_for_duration(&self) -> &str {
+        match self {
+            Duration::Temporary => "temporary",
+            Duration::Permanent => "permanent",
+        }
+    }
+}
+
+/// Builds the url a user visits to authorize your app.
+#[derive(Clone, Debug)]
+pub struct AuthorizeUrl {
+    base: String,
+    client_id: String,
+    redirect_uri: String,
+    state: String,
+    scopes: Vec<Scope>,
+    duration: Duration,
+    compact: bool,
+}
+
+impl AuthorizeUrl {
+    /// Create a new `AuthorizeUrl` instance. `base` is the auth host, e.g.
+    /// `https://www.reddit.com`.
+    pub fn new(base: &str, client_id: &str, redirect_uri: &str, state: &str) -> AuthorizeUrl {
+        AuthorizeUrl {
+            base: base.to_owned(),
+            client_id: client_id.to_owned(),
+            redirect_uri: redirect_uri.to_owned(),
+            state: state.to_owned(),
+            scopes: Vec::new(),
+            duration: Duration::Temporary,
+            compact: false,
+        }
+    }
+
+    /// Add a scope to request.
+    pub fn scope(mut self, scope: Scope) -> AuthorizeUrl {
+        self.scopes.push(scope);
+        self
+    }
+
+    /// Add several scopes to request.
+    pub fn scopes(mut self, scopes: &[Scope]) -> AuthorizeUrl {
+        self.scopes.extend_from_slice(scopes);
+        self
+    }
+
+    /// Set duration. Defaults to `Duration::Temporary`.
+    pub fn duration(mut self, duration: Duration) -> AuthorizeUrl {
+        self.duration = duration;
+        self
+    }
+
+    /// Use the compact authorization page, which is better suited to small screens.
+    pub fn compact(mut self, compact: bool) -> AuthorizeUrl {
+        self.compact = compact;
+        self
+    }
+
+    /// Build the url, failing if no redirect uri is set or the auth endpoint is not a valid
+    /// url.
+    pub fn build(&self) -> Result<String, RouxError> {
+        if self.redirect_uri.is_empty() {
+            return Err(RouxError::Credentials("redirect_uri"));
+        }
+
+        let path = if self.compact {
+            "api/v1/authorize.compact"
+        } else {
+            "api/v1/authorize"
+        };
+
+        let scope = self
+            .scopes
+            .iter()
+            .map(|scope| scope.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let params = [
+            ("client_id", &self.client_id[..]),
+            ("response_type", "code"),
+            ("state", &self.state[..]),
+            ("redirect_uri", &self.redirect_uri[..]),
+            ("duration", self.duration.get_string_for_duration()),
+            ("scope", &scope[..]),
+        ];
+
+        let mut url = Url::parse(&format!("{}/{}", self.base, path))
+            .map_err(|_| RouxError::Authorization("invalid auth endpoint".to_owned()))?;
+        url.query_pairs_mut().extend_pairs(params.iter());
+        Ok(url.to_string())
+    }
+}
+
+/// The parameters Reddit sends back to the redirect uri.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuthorizationCallback {
+    /// The one time code to exchange for a token.
+    pub code: Option<String>,
+    /// The state given to `AuthorizeUrl`.
+    pub state: Option<String>,
+    /// The reason authorization failed, e.g. `access_denied`.
+    pub error: Option<String>,
+}
+
+impl AuthorizationCallback {
+    /// Parse the redirect url, or just its query string.
+    pub fn parse(redirect: &str) -> Result<AuthorizationCallback, RouxError> {
+        let url = match Url::parse(redirect) {
+            Ok(url) => url,
+            Err(_) => Url::parse(&format!(
+                "http://localhost/?{}",
+                redirect.trim_start_matches('?')
+            ))
+            .map_err(|_| RouxError::Authorization("invalid redirect url".to_owned()))?,
+        };
+
+        let mut callback = AuthorizationCallback {
+            code: None,
+            state: None,
+            error: None,
+        };
+
+        for (key, value) in url.query_pairs() {
+            match &key[..] {
+                "code" => callback.code = Some(value.into_owned()),
+                "state" => callback.state = Some(value.into_owned()),
+                "error" => callback.error = Some(value.into_owned()),
+                _ => {}
+            }
+        }
+
+        Ok(callback)
+    }
+
+    /// Check the returned state matches the one the user was sent with and return the code.
+    pub fn verify(self, expected_state: &str) -> Result<String, RouxError> {
+        if let Some(error) = self.error {
+            return Err(RouxError::Authorization(error));
+        }
+
+        match self.state {
+            Some(ref state) if constant_time_eq(state.as_bytes(), expected_state.as_bytes()) => {}
+            _ => return Err(RouxError::Authorization("state mismatch".to_owned())),
+        }
+
+        self.code
+            .ok_or_else(|| RouxError::Authorization("missing code".to_owned()))
+    }
+}
+
+/// The response from the token endpoint. Reddit reports most failures, such as a wrong
+/// password, with a 200 status and an `error` field.
+#[derive(Deserialize, Debug)]
+struct AuthData {
+    access_token: Option<String>,
+    expires_in: Option<u64>,
+    refresh_token: Option<String>,
+    scope: Option<String>,
+    error: Option<String>,
+}
+
+/// An access token and what is needed to renew it.
+#[derive(Clone, Debug)]
+pub(crate) struct Token {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_at: Option<SystemTime>,
+    /// `None` if Reddit never said which scopes were granted.
+    pub scopes: Option<Vec<Scope>>,
+}
+
+impl Token {
+    pub fn new(access_token: &str) -> Token {
+        Token {
+            access_token: access_token.to_owned(),
+            refresh_token: None,
+            expires_at: None,
+            scopes: None,
+        }
+    }
+
+    /// Whether the token has expired or is about to.
+    pub fn is_expiring(&self) -> bool {
+        match self.expires_at {
+            Some(expires_at) => SystemTime::now() + RENEW_MARGIN >= expires_at,
+            None => false,
+        }
+    }
+
+    /// Replace the token with a renewed one. Reddit doesn't always send the refresh token
+    /// again, in which case the old one is still valid.
+    pub fn renew(&mut self, token: Token) {
+        let refresh_token = token.refresh_token.or_else(|| self.refresh_token.take());
+
+        *self = Token {
+            refresh_token,
+            ..token
+        };
+    }
+}
+
+/// Requests a new token from the auth host.
+pub(crate) async fn request_token<T: Serialize + ?Sized>(
+    config: &Config,
+    form: &T,
+) -> Result<Token, RouxError> {
+    let url = config.endpoints.build_auth("api/v1/access_token");
+
+    let request = Request::post(&url)
+        .header("user-agent", &config.user_agent)
+        .basic_auth(&config.client_id, &config.client_secret)
+        .form(form)?;
+
+    let response = config.transport.send(request).await?;
+
+    let auth_data = response.error_for_status()?.json::<AuthData>()?;
+
+    if let Some(error) = auth_data.error {
+        return Err(RouxError::Authorization(error));
+    }
+
+    let access_token = auth_data
+        .access_token
+        .ok_or_else(|| RouxError::Authorization("missing access token".to_owned()))?;
+
+    Ok(Token {
+        access_token,
+        refresh_token: auth_data.refresh_token,
+        expires_at: auth_data
+            .expires_in
+            .map(|secs| SystemTime::now() + StdDuration::from_secs(secs)),
+        scopes: auth_data.scope.map(|scope| Scope::parse_list(&scope)),
+    })
+}
+
+/// A logged in session that can be saved and restored later, so a restarting program
+/// doesn't have to log in again.
+///
+/// ```no_run
+/// use roux::Reddit;
+/// use roux::auth::Session;
+/// # use tokio_test;
+///
+/// # tokio_test::block_on(async {
+/// let me = Reddit::new("USER_AGENT", "CLIENT_ID", "CLIENT_SECRET")
+///     .username("USERNAME")
+///     .password("PASSWORD")
+///     .login()
+///     .await
+///     .unwrap();
+///
+/// let saved = serde_json::to_string(&me.session()).unwrap();
+///
+/// // Later on...
+/// let session: Session = serde_json::from_str(&saved).unwrap();
+/// let me = Reddit::new(&session.user_agent, &session.client_id, "CLIENT_SECRET")
+///     .restore(session)
+///     .unwrap();
+/// # })
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    /// Access token
+    pub access_token: String,
+    /// Refresh token, if Reddit issued one.
+    pub refresh_token: Option<String>,
+    /// When the access token expires, in seconds since the unix epoch.
+    pub expires_at: Option<u64>,
+    /// Scopes granted to the access token, or `None` if Reddit didn't say.
+    #[serde(default)]
+    pub scopes: Option<Vec<Scope>>,
+    /// User agent
+    pub user_agent: String,
+    /// Client ID
+    pub client_id: String,
+    /// Username, if logged in as a user with a password.
+    pub username: Option<String>,
+    /// How the session was logged in, used to log in again once the access token expires
+    /// and there is no refresh token. `None` for sessions saved before it was recorded.
+    #[serde(default)]
+    pub grant: Option<SessionGrant>,
+}
+
+/// How a `Session` was logged in.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum SessionGrant {
+    /// `Reddit::login`, which needs the password again to log in again.
+    Password,
+    /// `Reddit::login_app_only`
+    ClientCredentials,
+    /// `Reddit::login_installed`
+    Installed {
+        /// The device id used to log in.
+        device_id: String,
+    },
+    /// `Reddit::login_code`, which can't be repeated and relies on the refresh token.
+    AuthorizationCode,
+}
+
+impl SessionGrant {
+    fn new(grant: &Grant) -> SessionGrant {
+        match grant {
+            Grant::Password => SessionGrant::Password,
+            Grant::ClientCredentials => SessionGrant::ClientCredentials,
+            Grant::Installed(device_id) => SessionGrant::Installed {
+                device_id: device_id.to_owned(),
+            },
+            Grant::AuthorizationCode(_) => SessionGrant::AuthorizationCode,
+        }
+    }
+
+    /// The grant to log in again with, if this one can be repeated.
+    pub(crate) fn grant(&self) -> Option<Grant> {
+        match self {
+            SessionGrant::Password => Some(Grant::Password),
+            SessionGrant::ClientCredentials => Some(Grant::ClientCredentials),
+            SessionGrant::Installed { device_id } => Some(Grant::Installed(device_id.to_owned())),
+            SessionGrant::AuthorizationCode => None,
+        }
+    }
+}
+
+impl Session {
+    pub(crate) fn new(token: &Token, config: &Config) -> Session {
+        Session {
+            access_token: token.access_token.to_owned(),
+            refresh_token: token.refresh_token.to_owned(),
+            expires_at: token.expires_at.map(|expires_at| {
+                expires_at
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0)
+            }),
+            scopes: token.scopes.to_owned(),
+            user_agent: config.user_agent.to_owned(),
+            client_id: config.client_id.to_owned(),
+            username: config.username.to_owned(),
+            grant: config.grant.as_ref().map(SessionGrant::new),
+        }
+    }
+
+    pub(crate) fn token(&self) -> Token {
+        Token {
+            access_token: self.access_token.to_owned(),
+            refresh_token: self.refresh_token.to_owned(),
+            expires_at: self
+                .expires_at
+                .map(|secs| UNIX_EPOCH + StdDuration::from_secs(secs)),
+            scopes: self.scopes.to_owned(),
+        }
+    }
+}
+
+/// Generates a random string suitable for the `state` parameter, from the operating
+/// system's secure random number generator.
+pub fn generate_state() -> Result<String, RouxError> {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|_| RouxError::Authorization("no secure random source".to_owned()))?;
+
+    Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration as StdDuration, SystemTime};
+
+    use crate::config::{Config, Grant};
+
+    use super::{
+        generate_state, AuthorizationCallback, AuthorizeUrl, Duration, Scope, Session,
+        SessionGrant, Token,
+    };
+
+    #[test]
+    fn test_build_authorize_url() {
+        let url = AuthorizeUrl::new(
+            "https://www.reddit.com",
+            "abc",
+            "https://example.com/cb",
+            "xyz",
+        )
+        .scopes(&[Scope::Identity, Scope::Read])
+        .duration(Duration::Permanent)
+        .build()
+        .unwrap();
+
+        assert_eq!(
+            url,
+            "https://www.reddit.com/api/v1/authorize?client_id=abc&response_type=code&state=xyz\
+             &redirect_uri=https%3A%2F%2Fexample.com%2Fcb&duration=permanent&scope=identity+read"
+        );
+    }
+
+    #[test]
+    fn test_build_compact_authorize_url() {
+        let url = AuthorizeUrl::new("https://www.reddit.com", "abc", "cb", "xyz")
+            .compact(true)
+            .build()
+            .unwrap();
+
+        assert!(url.starts_with("https://www.reddit.com/api/v1/authorize.compact?"));
+        assert!(url.contains("duration=temporary"));
+    }
+
+    #[test]
+    fn test_callback_verify() {
+        let callback =
+            AuthorizationCallback::parse("https://example.com/cb?state=xyz&code=123").unwrap();
+        assert_eq!(callback.clone().verify("xyz").unwrap(), "123");
+        assert!(callback.verify("abc").is_err());
+
+        let callback = AuthorizationCallback::parse("?state=xyz&error=access_denied").unwrap();
+        assert_eq!(callback.error, Some("access_denied".to_owned()));
+        assert!(callback.verify("xyz").is_err());
+    }
+
+    #[test]
+    fn test_generate_state() {
+        let state = generate_state().unwrap();
+        assert_eq!(state.len(), 32);
+        assert_ne!(state, generate_state().unwrap());
+    }
+
+    #[test]
+    fn test_build_invalid_endpoint() {
+        let url = AuthorizeUrl::new("not a url", "abc", "cb", "xyz").build();
+        assert!(url.is_err());
+    }
+
+    #[test]
+    fn test_token_renew() {
+        let mut token = Token::new("old");
+        token.refresh_token = Some("refresh".to_owned());
+        token.expires_at = Some(SystemTime::now() + StdDuration::from_secs(30));
+        assert!(token.is_expiring());
+
+        token.renew(Token {
+            access_token: "new".to_owned(),
+            refresh_token: None,
+            expires_at: Some(SystemTime::now() + StdDuration::from_secs(3600)),
+            scopes: Some(vec![Scope::All]),
+        });
+
+        assert_eq!(token.access_token, "new");
+        assert_eq!(token.refresh_token, Some("refresh".to_owned()));
+        assert!(!token.is_expiring());
+    }
+
+    #[test]
+    fn test_session_round_trip() {
+        let mut config = Config::new("ua", "id", "secret");
+        config.username = Some("user".to_owned());
+        config.grant = Some(Grant::Password);
+
+        let mut token = Token::new("access");
+        token.refresh_token = Some("refresh".to_owned());
+        token.expires_at = Some(SystemTime::now() + StdDuration::from_secs(3600));
+        token.scopes = Some(vec![Scope::Read]);
+
+        let session = Session::new(&token, &config);
+        let json = serde_json::to_string(&session).unwrap();
+        let restored: Session = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, session);
+        assert_eq!(restored.grant, Some(SessionGrant::Password));
+
+        let restored = restored.token();
+        assert_eq!(restored.access_token, "access");
+        assert_eq!(restored.refresh_token, Some("refresh".to_owned()));
+        assert_eq!(restored.scopes, Some(vec![Scope::Read]));
+        assert!(!restored.is_expiring());
+    }
+}
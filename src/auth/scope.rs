@@ -0,0 +1,238 @@
+//! # Scopes
+//! OAuth scopes limit what an access token can be used for.
+//! See: <https://www.reddit.com/dev/api/oauth>
+
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// An OAuth scope.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum Scope {
+    /// Every scope. Granted to script apps logging in with a password.
+    All,
+    /// Update preferences and related account information.
+    Account,
+    /// Spend reddit gold creddits on giving gold to other users.
+    Creddits,
+    /// Edit and delete comments and submissions.
+    Edit,
+    /// Select subreddit and link flair.
+    Flair,
+    /// Access voting history and saved or hidden items.
+    History,
+    /// Access the username and signup date.
+    Identity,
+    /// Manage settings and contributors of live threads.
+    LiveManage,
+    /// Manage the configuration, sidebar and CSS of moderated subreddits.
+    ModConfig,
+    /// Add or remove approved submitters and ban or unban users.
+    ModContributors,
+    /// Manage and assign flair in moderated subreddits.
+    ModFlair,
+    /// Access the moderation log in moderated subreddits.
+    ModLog,
+    /// Access and manage modmail.
+    ModMail,
+    /// Invite or remove other moderators.
+    ModOthers,
+    /// Approve, remove, mark nsfw and distinguish content in moderated subreddits.
+    ModPosts,
+    /// Accept invitations to moderate a subreddit and abdicate.
+    ModSelf,
+    /// Access traffic stats in moderated subreddits.
+    ModTraffic,
+    /// Change editors and visibility of wiki pages in moderated subreddits.
+    ModWiki,
+    /// Access the list of subreddits the user moderates, contributes to and subscribes to.
+    MySubreddits,
+    /// Access the inbox and send private messages.
+    PrivateMessages,
+    /// Access posts and comments.
+    Read,
+    /// Report content for rules violations.
+    Report,
+    /// Save and unsave comments and submissions.
+    Save,
+    /// Edit structured styles for moderated subreddits.
+    StructuredStyles,
+    /// Submit links and comments.
+    Submit,
+    /// Manage subreddit subscriptions.
+    Subscribe,
+    /// Vote on submissions and comments.
+    Vote,
+    /// Edit wiki pages.
+    WikiEdit,
+    /// Read wiki pages.
+    WikiRead,
+    /// A scope roux doesn't know about yet.
+    Other(String),
+}
+
+impl Scope {
+    /// Every scope roux knows about, except `Scope::All`.
+    pub const KNOWN: [Scope; 28] = [
+        Scope::Account,
+        Scope::Creddits,
+        Scope::Edit,
+        Scope::Flair,
+        Scope::History,
+        Scope::Identity,
+        Scope::LiveManage,
+        Scope::ModConfig,
+        Scope::ModContributors,
+        Scope::ModFlair,
+        Scope::ModLog,
+        Scope::ModMail,
+        Scope::ModOthers,
+        Scope::ModPosts,
+        Scope::ModSelf,
+        Scope::ModTraffic,
+        Scope::ModWiki,
+        Scope::MySubreddits,
+        Scope::PrivateMessages,
+        Scope::Read,
+        Scope::Report,
+        Scope::Save,
+        Scope::StructuredStyles,
+        Scope::Submit,
+        Scope::Subscribe,
+        Scope::Vote,
+        Scope::WikiEdit,
+        Scope::WikiRead,
+    ];
+
+    /// Gets the request string for the scope
+    pub fn as_str(&self) -> &str {
+        match self {
+            Scope::All => "*",
+            Scope::Account => "account",
+            Scope::Creddits => "creddits",
+            Scope::Edit => "edit",
+            Scope::Flair => "flair",
+            Scope::History => "history",
+            Scope::Identity => "identity",
+            Scope::LiveManage => "livemanage",
+            Scope::ModConfig => "modconfig",
+            Scope::ModContributors => "modcontributors",
+            Scope::ModFlair => "modflair",
+            Scope::ModLog => "modlog",
+            Scope::ModMail => "modmail",
+            Scope::ModOthers => "modothers",
+            Scope::ModPosts => "modposts",
+            Scope::ModSelf => "modself",
+            Scope::ModTraffic => "modtraffic",
+            Scope::ModWiki => "modwiki",
+            Scope::MySubreddits => "mysubreddits",
+            Scope::PrivateMessages => "privatemessages",
+            Scope::Read => "read",
+            Scope::Report => "report",
+            Scope::Save => "save",
+            Scope::StructuredStyles => "structuredstyles",
+            Scope::Submit => "submit",
+            Scope::Subscribe => "subscribe",
+            Scope::Vote => "vote",
+            Scope::WikiEdit => "wikiedit",
+            Scope::WikiRead => "wikiread",
+            Scope::Other(scope) => scope,
+        }
+    }
+
+    /// Parses a list of scopes separated by spaces or commas, as found in token responses.
+    /// Scopes roux doesn't know about are kept as `Scope::Other`.
+    pub fn parse_list(scopes: &str) -> Vec<Scope> {
+        scopes
+            .split([' ', ','])
+            .filter(|scope| !scope.is_empty())
+            .map(Scope::from)
+            .collect()
+    }
+}
+
+impl From<&str> for Scope {
+    fn from(s: &str) -> Scope {
+        if s == "*" {
+            return Scope::All;
+        }
+
+        Scope::KNOWN
+            .iter()
+            .find(|scope| scope.as_str() == s)
+            .cloned()
+            .unwrap_or_else(|| Scope::Other(s.to_owned()))
+    }
+}
+
+impl From<String> for Scope {
+    fn from(s: String) -> Scope {
+        Scope::from(s.as_str())
+    }
+}
+
+impl From<Scope> for String {
+    fn from(scope: Scope) -> String {
+        match scope {
+            Scope::Other(scope) => scope,
+            scope => scope.as_str().to_owned(),
+        }
+    }
+}
+
+impl FromStr for Scope {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Scope::from(s))
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Scope;
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(
+            Scope::parse_list("identity modposts,wikiedit unknown"),
+            [
+                Scope::Identity,
+                Scope::ModPosts,
+                Scope::WikiEdit,
+                Scope::Other("unknown".to_owned())
+            ]
+        );
+        assert_eq!(Scope::parse_list("*"), [Scope::All]);
+        assert!(Scope::parse_list("").is_empty());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for scope in Scope::KNOWN.iter() {
+            assert_eq!(scope.as_str().parse::<Scope>(), Ok(scope.clone()));
+
+            let json = serde_json::to_string(scope).unwrap();
+            assert_eq!(json, format!("\"{}\"", scope));
+        }
+    }
+
+    #[test]
+    fn test_unknown_scope() {
+        let scope: Scope = serde_json::from_str("\"newscope\"").unwrap();
+        assert_eq!(scope, Scope::Other("newscope".to_owned()));
+        assert_eq!(serde_json::to_string(&scope).unwrap(), "\"newscope\"");
+
+        let all: Scope = serde_json::from_str("\"*\"").unwrap();
+        assert_eq!(all, Scope::All);
+    }
+}
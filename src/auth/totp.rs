@@ -0,0 +1,98 @@
+//! # TOTP
+//! Time based one time passwords (RFC 6238) as used by Reddit's two-factor authentication.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+
+/// How long each code is valid for, in seconds.
+const STEP: u64 = 30;
+/// Number of digits in each code.
+const DIGITS: u32 = 6;
+
+/// Generates the current code for a base32 encoded secret, or `None` if the secret is not
+/// valid base32.
+pub fn now(secret: &str) -> Option<String> {
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    at(secret, time)
+}
+
+/// Generates the code for a base32 encoded secret at a unix timestamp.
+pub fn at(secret: &str, time: u64) -> Option<String> {
+    let key = decode_base32(secret)?;
+    let mut mac = Hmac::<Sha1>::new_from_slice(&key).ok()?;
+    mac.update(&(time / STEP).to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    let offset = (hash[hash.len() - 1] & 0xf) as usize;
+    let binary = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+
+    Some(format!(
+        "{:0width$}",
+        binary % 10u32.pow(DIGITS),
+        width = DIGITS as usize
+    ))
+}
+
+/// Decodes RFC 4648 base32, ignoring case, spaces and padding.
+fn decode_base32(input: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for c in input.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+        let value = match c.to_ascii_uppercase() {
+            c @ 'A'..='Z' => c as u32 - 'A' as u32,
+            c @ '2'..='7' => c as u32 - '2' as u32 + 26,
+            _ => return None,
+        };
+
+        buffer = (buffer << 5) | value;
+        bits += 5;
+
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    if bytes.is_empty() {
+        None
+    } else {
+        Some(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{at, decode_base32};
+
+    // "12345678901234567890", the secret used by the RFC 6238 test vectors.
+    const SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn test_decode_base32() {
+        assert_eq!(decode_base32(SECRET).unwrap(), b"12345678901234567890");
+        assert_eq!(decode_base32("gezd gnbv").unwrap(), b"12345");
+        assert!(decode_base32("not base32!").is_none());
+    }
+
+    #[test]
+    fn test_rfc_6238_vectors() {
+        assert_eq!(at(SECRET, 59).unwrap(), "287082");
+        assert_eq!(at(SECRET, 1111111109).unwrap(), "081804");
+        assert_eq!(at(SECRET, 1234567890).unwrap(), "005924");
+        assert_eq!(at(SECRET, 2000000000).unwrap(), "279037");
+    }
+}
@@ -0,0 +1,232 @@
+use std::time::SystemTime;
+
+use futures_executor::{block_on, block_on_stream};
+
+use crate::auth::{Scope, Session};
+use crate::me::responses::{Inbox, InboxData, MeData, Scopes, Submitted};
+use crate::stream::PollOptions;
+use crate::subreddit::responses::{Submissions, SubmissionsData, SubredditCommentsData};
+use crate::transport::Response;
+use crate::util::{FeedOption, RouxError};
+
+use super::{Subreddit, User};
+
+/// Blocking version of `roux::Me`.
+pub struct Me {
+    inner: crate::Me,
+}
+
+impl Me {
+    pub(crate) fn from_async(inner: crate::Me) -> Me {
+        Me { inner }
+    }
+
+    /// The current access token.
+    pub fn access_token(&self) -> String {
+        self.inner.access_token()
+    }
+
+    /// The refresh token, if Reddit issued one.
+    pub fn refresh_token(&self) -> Option<String> {
+        self.inner.refresh_token()
+    }
+
+    /// When the current access token expires, if known.
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        self.inner.expires_at()
+    }
+
+    /// Scopes granted to the access token.
+    pub fn scopes(&self) -> Option<Vec<Scope>> {
+        self.inner.scopes()
+    }
+
+    /// Whether the access token was granted `scope`.
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.inner.has_scope(scope)
+    }
+
+    /// Export the session so it can be saved and restored with `Reddit::restore`.
+    pub fn session(&self) -> Session {
+        self.inner.session()
+    }
+
+    /// Get a new access token.
+    pub fn refresh(&self) -> Result<(), RouxError> {
+        block_on(self.inner.refresh())
+    }
+
+    /// Get a subreddit that reads through `oauth.reddit.com` with this user's access token.
+    pub fn subreddit(&self, name: &str) -> Subreddit {
+        Subreddit::from_async(self.inner.subreddit(name))
+    }
+
+    /// Get a user that reads through `oauth.reddit.com` with this user's access token.
+    pub fn user(&self, name: &str) -> User {
+        User::from_async(self.inner.user(name))
+    }
+
+    /// Get me
+    pub fn me(&self) -> Result<MeData, RouxError> {
+        block_on(self.inner.me())
+    }
+
+    /// Submit link
+    pub fn submit_link(&self, title: &str, link: &str, sr: &str) -> Result<Submitted, RouxError> {
+        block_on(self.inner.submit_link(title, link, sr))
+    }
+
+    /// Submit text
+    pub fn submit_text(&self, title: &str, text: &str, sr: &str) -> Result<Submitted, RouxError> {
+        block_on(self.inner.submit_text(title, text, sr))
+    }
+
+    /// Adds a friend to a subreddit with the specified type
+    pub fn add_subreddit_friend(
+        &self,
+        username: &str,
+        typ: &str,
+        sub: &str,
+    ) -> Result<bool, RouxError> {
+        block_on(self.inner.add_subreddit_friend(username, typ, sub))
+    }
+
+    /// Removes a friend to a subreddit with the specified type
+    pub fn remove_subreddit_friend(
+        &self,
+        username: &str,
+        typ: &str,
+        sub: &str,
+    ) -> Result<bool, RouxError> {
+        block_on(self.inner.remove_subreddit_friend(username, typ, sub))
+    }
+
+    /// Compose message
+    pub fn compose_message(
+        &self,
+        username: &str,
+        subject: &str,
+        body: &str,
+    ) -> Result<(), RouxError> {
+        block_on(self.inner.compose_message(username, subject, body))
+    }
+
+    /// Get the descriptions of every OAuth scope.
+    pub fn available_scopes(&self) -> Result<Scopes, RouxError> {
+        block_on(self.inner.available_scopes())
+    }
+
+    /// Get user's submitted posts.
+    pub fn inbox(&self) -> Result<Inbox, RouxError> {
+        block_on(self.inner.inbox())
+    }
+
+    /// Get saved
+    pub fn saved(&self) -> Result<Submissions, RouxError> {
+        block_on(self.inner.saved())
+    }
+
+    /// Get upvoted
+    pub fn upvoted(&self) -> Result<Submissions, RouxError> {
+        block_on(self.inner.upvoted())
+    }
+
+    /// Get downvoted
+    pub fn downvoted(&self) -> Result<Submissions, RouxError> {
+        block_on(self.inner.downvoted())
+    }
+
+    /// Get users unread messages
+    pub fn unread(&self) -> Result<Inbox, RouxError> {
+        block_on(self.inner.unread())
+    }
+
+    /// Iterate over new messages, comment replies and username mentions as they arrive,
+    /// oldest first. The iterator never ends.
+    pub fn stream_inbox(
+        &self,
+        options: Option<PollOptions>,
+    ) -> impl Iterator<Item = Result<InboxData, RouxError>> + '_ {
+        block_on_stream(Box::pin(self.inner.stream_inbox(options)))
+    }
+
+    /// Call `handler` with each new inbox item, marking it as read once `handler` succeeds
+    /// if `mark_read` is set. Runs until `handler` fails or polling fails in a way that
+    /// retrying won't fix.
+    pub fn handle_inbox<F, E>(
+        &self,
+        options: Option<PollOptions>,
+        mark_read: bool,
+        mut handler: F,
+    ) -> Result<(), E>
+    where
+        F: FnMut(InboxData) -> Result<(), E>,
+        E: From<RouxError>,
+    {
+        block_on(self.inner.handle_inbox(options, mark_read, |item| {
+            let result = handler(item);
+            async move { result }
+        }))
+    }
+
+    /// Mark messages as read
+    pub fn mark_read(&self, ids: &str) -> Result<Response, RouxError> {
+        block_on(self.inner.mark_read(ids))
+    }
+
+    /// Mark messages as unread
+    pub fn mark_unread(&self, ids: &str) -> Result<Response, RouxError> {
+        block_on(self.inner.mark_unread(ids))
+    }
+
+    /// Comment on the submission or comment with the fullname `parent`.
+    pub fn comment(&self, text: &str, parent: &str) -> Result<SubredditCommentsData, RouxError> {
+        block_on(self.inner.comment(text, parent))
+    }
+
+    /// Edit the text of the comment with the fullname `parent`.
+    pub fn edit(&self, text: &str, parent: &str) -> Result<SubredditCommentsData, RouxError> {
+        block_on(self.inner.edit(text, parent))
+    }
+
+    /// Logout
+    pub fn logout(self) -> Result<(), RouxError> {
+        block_on(self.inner.logout())
+    }
+
+    /// Iterate over user's inbox, up to `cap` messages.
+    pub fn inbox_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Iterator<Item = Result<InboxData, RouxError>> + '_ {
+        block_on_stream(Box::pin(self.inner.inbox_paginated(options, cap)))
+    }
+
+    /// Iterate over saved items, up to `cap` items.
+    pub fn saved_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Iterator<Item = Result<SubmissionsData, RouxError>> + '_ {
+        block_on_stream(Box::pin(self.inner.saved_paginated(options, cap)))
+    }
+
+    /// Iterate over upvoted posts, up to `cap` posts.
+    pub fn upvoted_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Iterator<Item = Result<SubmissionsData, RouxError>> + '_ {
+        block_on_stream(Box::pin(self.inner.upvoted_paginated(options, cap)))
+    }
+
+    /// Iterate over downvoted posts, up to `cap` posts.
+    pub fn downvoted_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Iterator<Item = Result<SubmissionsData, RouxError>> + '_ {
+        block_on_stream(Box::pin(self.inner.downvoted_paginated(options, cap)))
+    }
+}
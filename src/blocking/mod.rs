@@ -0,0 +1,146 @@
+//! # Blocking
+//! Synchronous versions of `Reddit`, `Me`, `Subreddit`, `Subreddits` and `User`, for
+//! programs that don't use an async runtime. Enable the `blocking` feature to use them.
+//!
+//! Each call blocks the current thread until the request completes, so don't use these
+//! types from inside an async runtime.
+//!
+//! ```no_run
+//! use roux::blocking::{Reddit, Subreddit};
+//!
+//! let hot = Subreddit::new("rust").hot(25, None);
+//!
+//! let me = Reddit::new("USER_AGENT", "CLIENT_ID", "CLIENT_SECRET")
+//!     .username("USERNAME")
+//!     .password("PASSWORD")
+//!     .login()
+//!     .unwrap();
+//!
+//! me.submit_text("TEXT_TITLE", "TEXT_BODY", "SUBREDDIT");
+//! ```
+
+use std::sync::{Arc, OnceLock};
+
+use futures_executor::block_on;
+
+use crate::auth::{AuthorizeUrl, Session};
+use crate::transport::{ReqwestBlockingTransport, Transport};
+use crate::util::{url::Endpoints, RateLimiter, RetryPolicy, RouxError};
+
+mod me;
+pub use me::Me;
+
+mod subreddit;
+pub use subreddit::{Subreddit, Subreddits};
+
+mod user;
+pub use user::User;
+
+/// The transport used when none is given, created on first use and then shared.
+fn default_transport() -> Arc<dyn Transport> {
+    static DEFAULT: OnceLock<Arc<dyn Transport>> = OnceLock::new();
+    DEFAULT
+        .get_or_init(|| Arc::new(ReqwestBlockingTransport::new()))
+        .clone()
+}
+
+/// Blocking client to use OAuth with Reddit.
+pub struct Reddit {
+    inner: crate::Reddit,
+}
+
+impl Reddit {
+    /// Creates a `Reddit` instance with user_agent, client_id, and client_secret.
+    pub fn new(user_agent: &str, client_id: &str, client_secret: &str) -> Reddit {
+        Reddit {
+            inner: crate::Reddit::new(user_agent, client_id, client_secret)
+                .transport(default_transport()),
+        }
+    }
+
+    /// Sets username.
+    pub fn username(mut self, username: &str) -> Reddit {
+        self.inner = self.inner.username(username);
+        self
+    }
+
+    /// Sets password.
+    pub fn password(mut self, password: &str) -> Reddit {
+        self.inner = self.inner.password(password);
+        self
+    }
+
+    /// Sets a two-factor authentication code, for accounts with 2FA enabled.
+    pub fn otp(mut self, otp: &str) -> Reddit {
+        self.inner = self.inner.otp(otp);
+        self
+    }
+
+    /// Sets the base32 encoded two-factor authentication secret.
+    #[cfg(feature = "totp")]
+    pub fn totp_secret(mut self, secret: &str) -> Reddit {
+        self.inner = self.inner.totp_secret(secret);
+        self
+    }
+
+    /// Sets the redirect uri registered for your app, used by the authorization code flow.
+    pub fn redirect_uri(mut self, redirect_uri: &str) -> Reddit {
+        self.inner = self.inner.redirect_uri(redirect_uri);
+        self
+    }
+
+    /// Sets the endpoints used for logging in and for every request made by the
+    /// resulting `Me`.
+    pub fn endpoints(mut self, endpoints: Endpoints) -> Reddit {
+        self.inner = self.inner.endpoints(endpoints);
+        self
+    }
+
+    /// Sets the rate limiter used by the resulting `Me`.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Reddit {
+        self.inner = self.inner.rate_limiter(rate_limiter);
+        self
+    }
+
+    /// Sets the retry policy used by the resulting `Me`.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Reddit {
+        self.inner = self.inner.retry_policy(retry_policy);
+        self
+    }
+
+    /// Sets the transport. It must complete requests without an async runtime.
+    pub fn transport(mut self, transport: Arc<dyn Transport>) -> Reddit {
+        self.inner = self.inner.transport(transport);
+        self
+    }
+
+    /// Login as a user.
+    pub fn login(self) -> Result<Me, RouxError> {
+        block_on(self.inner.login()).map(Me::from_async)
+    }
+
+    /// Login as the application itself, without a user context.
+    pub fn login_app_only(self) -> Result<Me, RouxError> {
+        block_on(self.inner.login_app_only()).map(Me::from_async)
+    }
+
+    /// Login as an installed app that has no client secret.
+    pub fn login_installed(self, device_id: &str) -> Result<Me, RouxError> {
+        block_on(self.inner.login_installed(device_id)).map(Me::from_async)
+    }
+
+    /// Creates the url to send a user to so they can authorize your app.
+    pub fn authorize_url(&self, state: &str) -> AuthorizeUrl {
+        self.inner.authorize_url(state)
+    }
+
+    /// Login as the user who authorized your app, exchanging the `code` from the redirect.
+    pub fn login_code(self, code: &str) -> Result<Me, RouxError> {
+        block_on(self.inner.login_code(code)).map(Me::from_async)
+    }
+
+    /// Restore a session saved with `Me::session`.
+    pub fn restore(self, session: Session) -> Result<Me, RouxError> {
+        self.inner.restore(session).map(Me::from_async)
+    }
+}
@@ -0,0 +1,335 @@
+use std::sync::Arc;
+
+use futures_executor::{block_on, block_on_stream};
+
+use crate::stream::PollOptions;
+use crate::subreddit::responses::{
+    CommentThing, Gilded, Moderators, Submissions, SubmissionsData, SubredditComments,
+    SubredditCommentsData, SubredditData, SubredditsListing,
+};
+use crate::transport::Transport;
+use crate::util::{
+    url::Endpoints, CommentOption, CommentSort, FeedOption, RateLimiter, RetryPolicy, RouxError,
+};
+
+/// Blocking version of `roux::Subreddits`.
+pub struct Subreddits {
+    inner: crate::Subreddits,
+}
+
+impl Subreddits {
+    /// Create a new `Subreddits` instance.
+    pub fn new() -> Subreddits {
+        Subreddits {
+            inner: crate::Subreddits::new().transport(super::default_transport()),
+        }
+    }
+
+    /// Sets the endpoints used for requests.
+    pub fn endpoints(mut self, endpoints: Endpoints) -> Subreddits {
+        self.inner = self.inner.endpoints(endpoints);
+        self
+    }
+
+    /// Sets the rate limiter, to share one budget between several clients.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Subreddits {
+        self.inner = self.inner.rate_limiter(rate_limiter);
+        self
+    }
+
+    /// Sets the retry policy.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Subreddits {
+        self.inner = self.inner.retry_policy(retry_policy);
+        self
+    }
+
+    /// Sets the transport. It must complete requests without an async runtime.
+    pub fn transport(mut self, transport: Arc<dyn Transport>) -> Subreddits {
+        self.inner = self.inner.transport(transport);
+        self
+    }
+
+    /// Search subreddits
+    pub fn search(
+        name: &str,
+        limit: Option<u32>,
+        options: Option<FeedOption>,
+    ) -> Result<SubredditsListing, RouxError> {
+        Subreddits::new().find(name, limit, options)
+    }
+
+    /// Search subreddits with the endpoints, rate limiter, retry policy and transport of
+    /// this instance.
+    pub fn find(
+        &self,
+        name: &str,
+        limit: Option<u32>,
+        options: Option<FeedOption>,
+    ) -> Result<SubredditsListing, RouxError> {
+        block_on(self.inner.find(name, limit, options))
+    }
+}
+
+impl Default for Subreddits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Blocking version of `roux::Subreddit`.
+pub struct Subreddit {
+    inner: crate::Subreddit,
+}
+
+impl Subreddit {
+    /// Create a new `Subreddit` instance.
+    pub fn new(name: &str) -> Subreddit {
+        Subreddit {
+            inner: crate::Subreddit::new(name).transport(super::default_transport()),
+        }
+    }
+
+    /// Create a `Subreddit` instance for the front page.
+    pub fn front_page() -> Subreddit {
+        Subreddit::new("")
+    }
+
+    /// Create a `Subreddit` instance for `r/all`.
+    pub fn all() -> Subreddit {
+        Subreddit::new("all")
+    }
+
+    /// Create a `Subreddit` instance for `r/popular`.
+    pub fn popular() -> Subreddit {
+        Subreddit::new("popular")
+    }
+
+    pub(crate) fn from_async(inner: crate::Subreddit) -> Subreddit {
+        Subreddit { inner }
+    }
+
+    /// Name of subreddit.
+    pub fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    /// Sets the endpoints used for requests.
+    pub fn endpoints(mut self, endpoints: Endpoints) -> Subreddit {
+        self.inner = self.inner.endpoints(endpoints);
+        self
+    }
+
+    /// Sets the rate limiter, to share one budget between several clients.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Subreddit {
+        self.inner = self.inner.rate_limiter(rate_limiter);
+        self
+    }
+
+    /// Sets the retry policy.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Subreddit {
+        self.inner = self.inner.retry_policy(retry_policy);
+        self
+    }
+
+    /// Sets the transport. It must complete requests without an async runtime.
+    pub fn transport(mut self, transport: Arc<dyn Transport>) -> Subreddit {
+        self.inner = self.inner.transport(transport);
+        self
+    }
+
+    /// Get moderators.
+    pub fn moderators(&self) -> Result<Moderators, RouxError> {
+        block_on(self.inner.moderators())
+    }
+
+    /// Get subreddit data.
+    pub fn about(&self) -> Result<SubredditData, RouxError> {
+        block_on(self.inner.about())
+    }
+
+    /// Get hot posts.
+    pub fn hot(&self, limit: u32, options: Option<FeedOption>) -> Result<Submissions, RouxError> {
+        block_on(self.inner.hot(limit, options))
+    }
+
+    /// Get rising posts.
+    pub fn rising(
+        &self,
+        limit: u32,
+        options: Option<FeedOption>,
+    ) -> Result<Submissions, RouxError> {
+        block_on(self.inner.rising(limit, options))
+    }
+
+    /// Get top posts.
+    pub fn top(&self, limit: u32, options: Option<FeedOption>) -> Result<Submissions, RouxError> {
+        block_on(self.inner.top(limit, options))
+    }
+
+    /// Get latest posts.
+    pub fn latest(
+        &self,
+        limit: u32,
+        options: Option<FeedOption>,
+    ) -> Result<Submissions, RouxError> {
+        block_on(self.inner.latest(limit, options))
+    }
+
+    /// Get controversial posts.
+    pub fn controversial(
+        &self,
+        limit: u32,
+        options: Option<FeedOption>,
+    ) -> Result<Submissions, RouxError> {
+        block_on(self.inner.controversial(limit, options))
+    }
+
+    /// Get the best posts. This only works on the front page, other subreddits fail with
+    /// `RouxError::InvalidOption`.
+    pub fn best(&self, limit: u32, options: Option<FeedOption>) -> Result<Submissions, RouxError> {
+        block_on(self.inner.best(limit, options))
+    }
+
+    /// Get gilded posts and comments.
+    pub fn gilded(&self, limit: u32, options: Option<FeedOption>) -> Result<Gilded, RouxError> {
+        block_on(self.inner.gilded(limit, options))
+    }
+
+    /// Get latest comments.
+    pub fn latest_comments(
+        &self,
+        depth: Option<u32>,
+        limit: Option<u32>,
+    ) -> Result<SubredditComments, RouxError> {
+        block_on(self.inner.latest_comments(depth, limit))
+    }
+
+    /// Get comments from article.
+    pub fn article_comments(
+        &self,
+        article: &str,
+        depth: Option<u32>,
+        limit: Option<u32>,
+    ) -> Result<SubredditComments, RouxError> {
+        block_on(self.inner.article_comments(article, depth, limit))
+    }
+
+    /// Get comments from article, sorted and focused on a single comment according to
+    /// `options`.
+    pub fn article_comments_with_options(
+        &self,
+        article: &str,
+        options: Option<CommentOption>,
+    ) -> Result<SubredditComments, RouxError> {
+        block_on(self.inner.article_comments_with_options(article, options))
+    }
+
+    /// Get the comments hidden behind "more" stubs of the submission with the fullname
+    /// `link_id`.
+    pub fn more_children(
+        &self,
+        link_id: &str,
+        children: &[String],
+        sort: Option<CommentSort>,
+    ) -> Result<Vec<CommentThing<SubredditCommentsData>>, RouxError> {
+        block_on(self.inner.more_children(link_id, children, sort))
+    }
+
+    /// Replace every "more" stub in `comments`, the comments of `article`.
+    pub fn expand_more(
+        &self,
+        article: &str,
+        comments: &mut SubredditComments,
+        sort: Option<CommentSort>,
+    ) -> Result<(), RouxError> {
+        block_on(self.inner.expand_more(article, comments, sort))
+    }
+
+    /// Get the full comment tree of `article`, expanding every "more" stub.
+    pub fn article_comments_full(
+        &self,
+        article: &str,
+        options: Option<CommentOption>,
+    ) -> Result<SubredditComments, RouxError> {
+        block_on(self.inner.article_comments_full(article, options))
+    }
+
+    /// Iterate over hot posts, up to `cap` posts.
+    pub fn hot_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Iterator<Item = Result<SubmissionsData, RouxError>> + '_ {
+        block_on_stream(Box::pin(self.inner.hot_paginated(options, cap)))
+    }
+
+    /// Iterate over rising posts, up to `cap` posts.
+    pub fn rising_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Iterator<Item = Result<SubmissionsData, RouxError>> + '_ {
+        block_on_stream(Box::pin(self.inner.rising_paginated(options, cap)))
+    }
+
+    /// Iterate over top posts, up to `cap` posts.
+    pub fn top_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Iterator<Item = Result<SubmissionsData, RouxError>> + '_ {
+        block_on_stream(Box::pin(self.inner.top_paginated(options, cap)))
+    }
+
+    /// Iterate over latest posts, up to `cap` posts.
+    pub fn latest_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Iterator<Item = Result<SubmissionsData, RouxError>> + '_ {
+        block_on_stream(Box::pin(self.inner.latest_paginated(options, cap)))
+    }
+
+    /// Iterate over new posts as they are submitted, oldest first. The iterator never ends.
+    pub fn stream_submissions(
+        &self,
+        options: Option<PollOptions>,
+    ) -> impl Iterator<Item = Result<SubmissionsData, RouxError>> + '_ {
+        block_on_stream(Box::pin(self.inner.stream_submissions(options)))
+    }
+
+    /// Iterate over new comments as they are posted, oldest first. The iterator never ends.
+    pub fn stream_comments(
+        &self,
+        options: Option<PollOptions>,
+    ) -> impl Iterator<Item = Result<SubredditCommentsData, RouxError>> + '_ {
+        block_on_stream(Box::pin(self.inner.stream_comments(options)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::Subreddit;
+    use crate::transport::{Method, MockTransport, Response};
+
+    #[test]
+    fn test_blocking_with_mock() {
+        let mock = Arc::new(MockTransport::new());
+        mock.route(
+            Method::Get,
+            "/r/rust/hot.json?limit=5",
+            Response::new(
+                200,
+                r#"{"kind": "Listing", "data": {"after": "t3_abc", "children": []}}"#,
+            ),
+        );
+
+        let subreddit = Subreddit::new("rust").transport(mock.clone());
+        let hot = subreddit.hot(5, None).unwrap();
+
+        assert_eq!(hot.data.after, Some("t3_abc".to_owned()));
+        assert_eq!(mock.requests().len(), 1);
+    }
+}
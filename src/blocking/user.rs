@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use futures_executor::{block_on, block_on_stream};
+
+use crate::subreddit::responses::{
+    Submissions, SubmissionsData, SubredditComments, SubredditCommentsData,
+};
+use crate::transport::Transport;
+use crate::user::responses::{Overview, OverviewData};
+use crate::util::{url::Endpoints, FeedOption, RateLimiter, RetryPolicy, RouxError};
+
+/// Blocking version of `roux::User`.
+pub struct User {
+    inner: crate::User,
+}
+
+impl User {
+    /// Create a new `User` instance.
+    pub fn new(user: &str) -> User {
+        User {
+            inner: crate::User::new(user).transport(super::default_transport()),
+        }
+    }
+
+    pub(crate) fn from_async(inner: crate::User) -> User {
+        User { inner }
+    }
+
+    /// User's name.
+    pub fn name(&self) -> &str {
+        &self.inner.user
+    }
+
+    /// Sets the endpoints used for requests.
+    pub fn endpoints(mut self, endpoints: Endpoints) -> User {
+        self.inner = self.inner.endpoints(endpoints);
+        self
+    }
+
+    /// Sets the rate limiter, to share one budget between several clients.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> User {
+        self.inner = self.inner.rate_limiter(rate_limiter);
+        self
+    }
+
+    /// Sets the retry policy.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> User {
+        self.inner = self.inner.retry_policy(retry_policy);
+        self
+    }
+
+    /// Sets the transport. It must complete requests without an async runtime.
+    pub fn transport(mut self, transport: Arc<dyn Transport>) -> User {
+        self.inner = self.inner.transport(transport);
+        self
+    }
+
+    /// Get user's overview.
+    pub fn overview(&self, options: Option<FeedOption>) -> Result<Overview, RouxError> {
+        block_on(self.inner.overview(options))
+    }
+
+    /// Get user's submitted posts.
+    pub fn submitted(&self, options: Option<FeedOption>) -> Result<Submissions, RouxError> {
+        block_on(self.inner.submitted(options))
+    }
+
+    /// Get user's submitted comments.
+    pub fn comments(&self, options: Option<FeedOption>) -> Result<SubredditComments, RouxError> {
+        block_on(self.inner.comments(options))
+    }
+
+    /// Iterate over user's overview, up to `cap` items.
+    pub fn overview_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Iterator<Item = Result<OverviewData, RouxError>> + '_ {
+        block_on_stream(Box::pin(self.inner.overview_paginated(options, cap)))
+    }
+
+    /// Iterate over user's submitted posts, up to `cap` posts.
+    pub fn submitted_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Iterator<Item = Result<SubmissionsData, RouxError>> + '_ {
+        block_on_stream(Box::pin(self.inner.submitted_paginated(options, cap)))
+    }
+
+    /// Iterate over user's comments, up to `cap` comments.
+    pub fn comments_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Iterator<Item = Result<SubredditCommentsData, RouxError>> + '_ {
+        block_on_stream(Box::pin(self.inner.comments_paginated(options, cap)))
+    }
+}
@@ -1,10 +1,68 @@
+use std::sync::Arc;
+
+use crate::transport::{default_transport, Transport};
+use crate::util::{url::Endpoints, RateLimiter, RetryPolicy};
+
+/// The grant used to get an access token.
+#[derive(Clone, Debug)]
+pub enum Grant {
+    Password,
+    ClientCredentials,
+    Installed(String),
+    AuthorizationCode(String),
+}
+
+impl Grant {
+    /// Builds the form for requesting a token, or the name of the missing credential.
+    pub fn form(&self, config: &Config) -> Result<Vec<(&'static str, String)>, &'static str> {
+        match self {
+            Grant::Password => Ok(vec![
+                ("grant_type", "password".to_owned()),
+                ("username", config.username.to_owned().ok_or("username")?),
+                ("password", config.password_with_otp()?),
+            ]),
+            Grant::ClientCredentials => Ok(vec![("grant_type", "client_credentials".to_owned())]),
+            Grant::Installed(device_id) => Ok(vec![
+                (
+                    "grant_type",
+                    "https://oauth.reddit.com/grants/installed_client".to_owned(),
+                ),
+                ("device_id", device_id.to_owned()),
+            ]),
+            Grant::AuthorizationCode(code) => Ok(vec![
+                ("grant_type", "authorization_code".to_owned()),
+                ("code", code.to_owned()),
+                (
+                    "redirect_uri",
+                    config.redirect_uri.to_owned().ok_or("redirect_uri")?,
+                ),
+            ]),
+        }
+    }
+
+    /// Whether the grant can be used again to get a new token. Authorization codes can
+    /// only be exchanged once.
+    pub fn is_repeatable(&self) -> bool {
+        !matches!(self, Grant::AuthorizationCode(_))
+    }
+}
+
 pub struct Config {
     pub user_agent: String,
     pub client_id: String,
     pub client_secret: String,
     pub username: Option<String>,
     pub password: Option<String>,
+    pub otp: Option<String>,
+    #[cfg(feature = "totp")]
+    pub totp_secret: Option<String>,
     pub access_token: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub grant: Option<Grant>,
+    pub endpoints: Endpoints,
+    pub rate_limiter: RateLimiter,
+    pub retry_policy: RetryPolicy,
+    pub transport: Arc<dyn Transport>,
 }
 
 impl Config {
@@ -15,7 +73,36 @@ impl Config {
             client_secret: client_secret.to_owned(),
             username: None,
             password: None,
+            otp: None,
+            #[cfg(feature = "totp")]
+            totp_secret: None,
             access_token: None,
+            redirect_uri: None,
+            grant: None,
+            endpoints: Endpoints::new(),
+            rate_limiter: RateLimiter::new(),
+            retry_policy: RetryPolicy::new(),
+            transport: default_transport(),
+        }
+    }
+
+    /// The password, with a two-factor code appended as `password:otp` if there is one.
+    /// A code generated from the TOTP secret is preferred over a fixed one, which will
+    /// have expired by the time the token needs renewing.
+    fn password_with_otp(&self) -> Result<String, &'static str> {
+        let password = self.password.to_owned().ok_or("password")?;
+
+        #[cfg(feature = "totp")]
+        {
+            if let Some(ref secret) = self.totp_secret {
+                let otp = crate::auth::totp::now(secret).ok_or("totp_secret")?;
+                return Ok(format!("{}:{}", password, otp));
+            }
+        }
+
+        match self.otp {
+            Some(ref otp) => Ok(format!("{}:{}", password, otp)),
+            None => Ok(password),
         }
     }
 }
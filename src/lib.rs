@@ -24,6 +24,36 @@
 //!
 //! This will authticate you as the user given in the username function.
 //!
+//! ## Application only OAuth
+//! If you only need to read public data you can log in as the application itself. This
+//! does not need a Reddit account but still gets the OAuth rate limits.
+//! ```no_run
+//! use roux::Reddit;
+//! # use tokio_test;
+//!
+//! # tokio_test::block_on(async {
+//! let client = Reddit::new("USER_AGENT", "CLIENT_ID", "CLIENT_SECRET")
+//!     .login_app_only()
+//!     .await;
+//!
+//! let me = client.unwrap();
+//! # })
+//! ```
+//!
+//! Installed apps that ship without a client secret can log in with a device id instead.
+//! ```no_run
+//! use roux::Reddit;
+//! # use tokio_test;
+//!
+//! # tokio_test::block_on(async {
+//! let client = Reddit::new("USER_AGENT", "CLIENT_ID", "")
+//!     .login_installed("DO_NOT_TRACK_THIS_DEVICE")
+//!     .await;
+//!
+//! let me = client.unwrap();
+//! # })
+//! ```
+//!
 //! ## Usage
 //! Using the OAuth client, you can:
 //!
@@ -63,12 +93,13 @@
 //! # })
 //! ```
 
-use serde::Deserialize;
-
-use reqwest::header::USER_AGENT;
-use reqwest::Client;
+use std::sync::Arc;
 
 mod config;
+use config::Grant;
+
+/// Auth module.
+pub mod auth;
 
 /// Subreddit module.
 pub mod subreddit;
@@ -84,19 +115,24 @@ pub use me::Me;
 
 pub mod responses;
 
+/// Transport module.
+pub mod transport;
+
+/// Stream module.
+pub mod stream;
+
+/// Blocking module.
+#[cfg(feature = "blocking")]
+pub mod blocking;
+use transport::Transport;
+
 /// Utils for requests.
 pub mod util;
-use util::url;
+use util::{url::Endpoints, RateLimiter, RetryPolicy};
 
 /// Client to use OAuth with Reddit.
 pub struct Reddit {
     config: config::Config,
-    client: Client,
-}
-
-#[derive(Deserialize, Debug)]
-struct AuthData {
-    pub access_token: String,
 }
 
 impl Reddit {
@@ -104,7 +140,6 @@ impl Reddit {
     pub fn new(user_agent: &str, client_id: &str, client_secret: &str) -> Reddit {
         Reddit {
             config: config::Config::new(user_agent, client_id, client_secret),
-            client: Client::new(),
         }
     }
 
@@ -120,29 +155,320 @@ impl Reddit {
         self
     }
 
+    /// Sets a two-factor authentication code, for accounts with 2FA enabled.
+    ///
+    /// Codes are only valid for a short time, so logging in again once the access token
+    /// expires will fail. Use `totp_secret` for long running programs.
+    pub fn otp(mut self, otp: &str) -> Reddit {
+        self.config.otp = Some(otp.to_owned());
+        self
+    }
+
+    /// Sets the base32 encoded two-factor authentication secret, from which a fresh code is
+    /// generated each time roux logs in.
+    #[cfg(feature = "totp")]
+    pub fn totp_secret(mut self, secret: &str) -> Reddit {
+        self.config.totp_secret = Some(secret.to_owned());
+        self
+    }
+
+    /// Sets the redirect uri registered for your app, used by the authorization code flow.
+    pub fn redirect_uri(mut self, redirect_uri: &str) -> Reddit {
+        self.config.redirect_uri = Some(redirect_uri.to_owned());
+        self
+    }
+
+    /// Sets the endpoints used for logging in and for every request made by the
+    /// resulting `Me`.
+    pub fn endpoints(mut self, endpoints: Endpoints) -> Reddit {
+        self.config.endpoints = endpoints;
+        self
+    }
+
+    /// Sets the rate limiter used by the resulting `Me`, to share one budget between
+    /// several clients.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Reddit {
+        self.config.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// Sets the retry policy used by the resulting `Me`.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Reddit {
+        self.config.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets the transport used for logging in and for every request made by the
+    /// resulting `Me`.
+    pub fn transport(mut self, transport: Arc<dyn Transport>) -> Reddit {
+        self.config.transport = transport;
+        self
+    }
+
     /// Login as a user.
     pub async fn login(self) -> Result<me::Me, util::RouxError> {
-        let url = &url::build_url("api/v1/access_token")[..];
-        let form = [
-            ("grant_type", "password"),
-            ("username", &self.config.username.to_owned().unwrap()),
-            ("password", &self.config.password.to_owned().unwrap()),
-        ];
-
-        let request = self
-            .client
-            .post(url)
-            .header(USER_AGENT, &self.config.user_agent[..])
-            .basic_auth(&self.config.client_id, Some(&self.config.client_secret))
-            .form(&form);
-
-        let response = request.send().await?;
-
-        if response.status() == 200 {
-            let auth_data = response.json::<AuthData>().await.unwrap();
-            Ok(me::Me::new(&auth_data.access_token, self.config))
-        } else {
-            Err(util::RouxError::Status(response))
+        self.authenticate(Grant::Password).await
+    }
+
+    /// Login as the application itself, without a user context.
+    ///
+    /// This uses the `client_credentials` grant, so the resulting `Me` gets OAuth rate
+    /// limits but can only read public data. Methods that act on a user's account will
+    /// fail.
+    pub async fn login_app_only(self) -> Result<me::Me, util::RouxError> {
+        self.authenticate(Grant::ClientCredentials).await
+    }
+
+    /// Login as an installed app that has no client secret.
+    ///
+    /// Installed apps such as desktop or mobile programs cannot keep a secret, so create
+    /// the `Reddit` instance with an empty `client_secret`. `device_id` should be a unique,
+    /// 20-30 character string that stays the same for each device, or
+    /// `DO_NOT_TRACK_THIS_DEVICE` if you don't want to identify it.
+    pub async fn login_installed(self, device_id: &str) -> Result<me::Me, util::RouxError> {
+        self.authenticate(Grant::Installed(device_id.to_owned()))
+            .await
+    }
+
+    /// Creates the url to send a user to so they can authorize your app. `state` should be
+    /// a random string, see `auth::generate_state`, that is checked when the user returns.
+    pub fn authorize_url(&self, state: &str) -> auth::AuthorizeUrl {
+        let redirect_uri = self.config.redirect_uri.as_deref().unwrap_or_default();
+
+        auth::AuthorizeUrl::new(
+            &self.config.endpoints.auth,
+            &self.config.client_id,
+            redirect_uri,
+            state,
+        )
+    }
+
+    /// Login as the user who authorized your app, exchanging the `code` from the redirect.
+    ///
+    /// Ask for `Duration::Permanent` when building the authorize url if the session needs
+    /// to outlive the first access token.
+    pub async fn login_code(self, code: &str) -> Result<me::Me, util::RouxError> {
+        self.authenticate(Grant::AuthorizationCode(code.to_owned()))
+            .await
+    }
+
+    /// Restore a session saved with `Me::session`.
+    ///
+    /// Once the access token expires and there is no refresh token, the session logs in
+    /// again the way it was created. Password sessions need the password to be set again.
+    pub fn restore(mut self, session: auth::Session) -> Result<me::Me, util::RouxError> {
+        if session.client_id != self.config.client_id {
+            return Err(util::RouxError::Authorization(
+                "session belongs to a different client".to_owned(),
+            ));
         }
+
+        if self.config.username.is_none() {
+            self.config.username = session.username.to_owned();
+        }
+
+        self.config.grant = match session.grant {
+            Some(ref grant) => grant.grant(),
+            // Sessions saved before the grant was recorded.
+            None if self.config.password.is_some() => Some(Grant::Password),
+            None => None,
+        };
+
+        Ok(me::Me::from_token(session.token(), self.config))
+    }
+
+    async fn authenticate(mut self, grant: Grant) -> Result<me::Me, util::RouxError> {
+        let form = grant
+            .form(&self.config)
+            .map_err(util::RouxError::Credentials)?;
+        let token = auth::request_token(&self.config, &form).await?;
+
+        self.config.grant = Some(grant);
+        Ok(me::Me::from_token(token, self.config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::Reddit;
+    use crate::auth::{Session, SessionGrant};
+    use crate::transport::{Method, MockTransport, Response};
+    use crate::util::RouxError;
+
+    fn token_route(mock: &MockTransport) {
+        mock.route(
+            Method::Post,
+            "api/v1/access_token",
+            Response::new(200, r#"{"access_token": "token", "expires_in": 3600}"#),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_login_app_only() {
+        let mock = Arc::new(MockTransport::new());
+        token_route(&mock);
+
+        let me = Reddit::new("ua", "id", "secret")
+            .transport(mock.clone())
+            .login_app_only()
+            .await
+            .unwrap();
+        assert_eq!(me.access_token(), "token");
+
+        let requests = mock.requests();
+        assert_eq!(
+            requests[0].body,
+            Some("grant_type=client_credentials".to_owned())
+        );
+        assert_eq!(
+            requests[0].header_value("authorization"),
+            Some("Basic aWQ6c2VjcmV0")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_login_installed() {
+        let mock = Arc::new(MockTransport::new());
+        token_route(&mock);
+
+        Reddit::new("ua", "id", "")
+            .transport(mock.clone())
+            .login_installed("DO_NOT_TRACK_THIS_DEVICE")
+            .await
+            .unwrap();
+
+        let requests = mock.requests();
+        assert_eq!(
+            requests[0].body,
+            Some(
+                "grant_type=https%3A%2F%2Foauth.reddit.com%2Fgrants%2Finstalled_client\
+                 &device_id=DO_NOT_TRACK_THIS_DEVICE"
+                    .to_owned()
+            )
+        );
+        assert_eq!(
+            requests[0].header_value("authorization"),
+            Some("Basic aWQ6")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restore_installed_session() {
+        let mock = Arc::new(MockTransport::new());
+        token_route(&mock);
+        token_route(&mock);
+
+        let me = Reddit::new("ua", "id", "")
+            .transport(mock.clone())
+            .login_installed("device")
+            .await
+            .unwrap();
+
+        let saved = serde_json::to_string(&me.session()).unwrap();
+        let session: Session = serde_json::from_str(&saved).unwrap();
+        assert_eq!(
+            session.grant,
+            Some(SessionGrant::Installed {
+                device_id: "device".to_owned()
+            })
+        );
+
+        let me = Reddit::new("ua", "id", "")
+            .transport(mock.clone())
+            .restore(session)
+            .unwrap();
+        me.refresh().await.unwrap();
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1]
+            .body
+            .as_ref()
+            .unwrap()
+            .contains("device_id=device"));
+    }
+
+    #[tokio::test]
+    async fn test_login_with_otp() {
+        let mock = Arc::new(MockTransport::new());
+        token_route(&mock);
+
+        Reddit::new("ua", "id", "secret")
+            .username("user")
+            .password("pass")
+            .otp("123456")
+            .transport(mock.clone())
+            .login()
+            .await
+            .unwrap();
+
+        let requests = mock.requests();
+        assert_eq!(
+            requests[0].body,
+            Some("grant_type=password&username=user&password=pass%3A123456".to_owned())
+        );
+    }
+
+    #[cfg(feature = "totp")]
+    #[tokio::test]
+    async fn test_login_with_totp_secret() {
+        // "12345678901234567890", the secret used by the RFC 6238 test vectors.
+        let secret = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+        let mock = Arc::new(MockTransport::new());
+        token_route(&mock);
+
+        let before = crate::auth::totp::now(secret).unwrap();
+        Reddit::new("ua", "id", "secret")
+            .username("user")
+            .password("pass")
+            .otp("123456")
+            .totp_secret(secret)
+            .transport(mock.clone())
+            .login()
+            .await
+            .unwrap();
+        let after = crate::auth::totp::now(secret).unwrap();
+
+        // The code may change while logging in.
+        let body = mock.requests()[0].body.clone().unwrap();
+        assert!(
+            [before, after]
+                .iter()
+                .any(|otp| body
+                    == format!("grant_type=password&username=user&password=pass%3A{}", otp)),
+            "unexpected body {}",
+            body
+        );
+    }
+
+    #[test]
+    fn test_authorize_url_without_redirect_uri() {
+        let url = Reddit::new("ua", "id", "secret")
+            .authorize_url("state")
+            .build();
+        assert!(matches!(url, Err(RouxError::Credentials("redirect_uri"))));
+
+        let url = Reddit::new("ua", "id", "secret")
+            .redirect_uri("https://example.com/cb")
+            .authorize_url("state")
+            .build();
+        assert!(url.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_login_missing_credentials() {
+        let mock = Arc::new(MockTransport::new());
+
+        let result = Reddit::new("ua", "id", "secret")
+            .username("user")
+            .transport(mock.clone())
+            .login()
+            .await;
+
+        assert!(matches!(result, Err(RouxError::Credentials("password"))));
+        assert!(mock.requests().is_empty());
     }
 }
@@ -1,74 +1,176 @@
 //! # Me
 //! Me module.
 
-extern crate reqwest;
 extern crate serde_json;
 
-use reqwest::{header, Client, Response};
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+use futures_util::lock::Mutex;
+use futures_util::stream::{Stream, StreamExt};
+use serde::de::{DeserializeOwned, Error as _};
 use serde::Serialize;
 
+use crate::auth::{self, Scope, Session, Token};
 use crate::config::Config;
-use crate::util::{url, RouxError};
+use crate::stream::{paginate, poll, PollOptions};
+use crate::transport::{self, Request, Response};
+use crate::util::option::push_options;
+use crate::util::{FeedOption, RouxError};
 
 pub mod responses;
 
-use crate::subreddit::responses::Submissions;
-use responses::{Friend, Inbox, MeData};
+use crate::subreddit::responses::{Submissions, SubmissionsData, SubredditCommentsData};
+use crate::subreddit::Subreddit;
+use crate::user::User;
+use responses::{Friend, Inbox, InboxData, JsonResponse, MeData, Scopes, Submitted, Things};
 
 /// Me
+///
+/// The access token is renewed shortly before it expires, or after Reddit rejects it, using
+/// the refresh token if there is one and otherwise the grant used to log in.
 pub struct Me {
     /// Access token
+    #[deprecated(
+        note = "this is the token `Me` was created with and is not updated when it \
+                         is renewed, use `Me::access_token()` instead"
+    )]
     pub access_token: String,
-    client: Client,
-    config: Config,
+    auth: Arc<Authenticated>,
+}
+
+/// The credentials of a `Me`, shared with the `Subreddit` and `User` handles created from it.
+pub(crate) struct Authenticated {
+    pub(crate) config: Config,
+    token: RwLock<Token>,
+    /// Held while the token is renewed, so concurrent requests renew it only once.
+    refreshing: Mutex<()>,
 }
 
 impl Me {
     /// Create a new `me`
     pub fn new(access_token: &str, config: Config) -> Me {
-        let mut headers = header::HeaderMap::new();
+        Me::from_token(Token::new(access_token), config)
+    }
 
-        headers.insert(
-            header::AUTHORIZATION,
-            header::HeaderValue::from_str(&format!("Bearer {}", access_token)).unwrap(),
-        );
+    #[allow(deprecated)]
+    pub(crate) fn from_token(token: Token, config: Config) -> Me {
+        Me {
+            access_token: token.access_token.to_owned(),
+            auth: Arc::new(Authenticated {
+                config,
+                token: RwLock::new(token),
+                refreshing: Mutex::new(()),
+            }),
+        }
+    }
 
-        headers.insert(
-            header::USER_AGENT,
-            header::HeaderValue::from_str(&config.user_agent[..]).unwrap(),
-        );
+    /// The current access token.
+    pub fn access_token(&self) -> String {
+        self.auth.access_token()
+    }
 
-        let client = Client::builder().default_headers(headers).build().unwrap();
+    /// The refresh token, if Reddit issued one.
+    pub fn refresh_token(&self) -> Option<String> {
+        self.auth.token.read().unwrap().refresh_token.to_owned()
+    }
 
-        Me {
-            access_token: access_token.to_owned(),
-            config,
-            client,
+    /// When the current access token expires, if known.
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        self.auth.token.read().unwrap().expires_at
+    }
+
+    /// Scopes granted to the access token. This is `None` if Reddit didn't say, such as
+    /// when `Me` is created from just an access token.
+    pub fn scopes(&self) -> Option<Vec<Scope>> {
+        self.auth.token.read().unwrap().scopes.to_owned()
+    }
+
+    /// Whether the access token was granted `scope`. Assumed to be true when the granted
+    /// scopes are not known.
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        match self.auth.token.read().unwrap().scopes {
+            Some(ref scopes) => scopes.contains(&Scope::All) || scopes.contains(&scope),
+            None => true,
         }
     }
 
+    fn require(&self, scope: Scope) -> Result<(), RouxError> {
+        if self.has_scope(scope.clone()) {
+            Ok(())
+        } else {
+            Err(RouxError::MissingScope(scope))
+        }
+    }
+
+    /// Export the session so it can be saved and restored with `Reddit::restore`.
+    pub fn session(&self) -> Session {
+        Session::new(&self.auth.token.read().unwrap(), &self.auth.config)
+    }
+
+    /// Get a new access token.
+    pub async fn refresh(&self) -> Result<(), RouxError> {
+        self.auth.refresh().await
+    }
+
+    /// Get a subreddit that reads through `oauth.reddit.com` with this user's access token,
+    /// which is needed for private subreddits and endpoints such as `moderators`.
+    pub fn subreddit(&self, name: &str) -> Subreddit {
+        Subreddit::new(name).authenticated(self)
+    }
+
+    /// Get a user that reads through `oauth.reddit.com` with this user's access token.
+    pub fn user(&self, name: &str) -> User {
+        User::new(name).authenticated(self)
+    }
+
+    pub(crate) fn auth(&self) -> Arc<Authenticated> {
+        self.auth.clone()
+    }
+
     async fn get(&self, url: &str) -> Result<Response, RouxError> {
-        let get_url = url::build_oauth(url);
+        let get_url = self.auth.config.endpoints.build_oauth(url);
 
-        match self.client.get(&get_url[..]).send().await {
-            Ok(response) => Ok(response),
-            Err(e) => Err(e.into()),
-        }
+        self.auth.send(false, Request::get(&get_url)).await
     }
 
     async fn post<T: Serialize>(&self, url: &str, form: T) -> Result<Response, RouxError> {
-        let post_url = url::build_oauth(url).to_owned();
+        let post_url = self.auth.config.endpoints.build_oauth(url);
+
+        self.auth
+            .send(true, Request::post(&post_url).form(&form)?)
+            .await?
+            .error_for_api()
+    }
+
+    /// Posts a form and returns the comment Reddit sends back.
+    async fn post_thing<T: Serialize>(
+        &self,
+        url: &str,
+        form: T,
+    ) -> Result<SubredditCommentsData, RouxError> {
+        let response = self.post(url, form).await?;
+        let mut things = response
+            .json::<JsonResponse<Things<SubredditCommentsData>>>()?
+            .json
+            .data
+            .things;
 
-        match self.client.post(&post_url[..]).form(&form).send().await {
-            Ok(response) => Ok(response),
-            Err(e) => Err(e.into()),
+        match things.pop() {
+            Some(thing) => Ok(thing.data),
+            None => Err(RouxError::Parse(serde_json::Error::custom(
+                "response has no things",
+            ))),
         }
     }
 
     /// Get me
     pub async fn me(&self) -> Result<MeData, RouxError> {
+        self.require(Scope::Identity)?;
+
         match self.get("api/v1/me").await {
-            Ok(res) => Ok(res.json::<MeData>().await?),
+            Ok(res) => res.json::<MeData>(),
             Err(e) => Err(e),
         }
     }
@@ -79,15 +181,23 @@ impl Me {
         title: &str,
         link: &str,
         sr: &str,
-    ) -> Result<Response, RouxError> {
+    ) -> Result<Submitted, RouxError> {
+        self.require(Scope::Submit)?;
+
         let form = [
+            ("api_type", "json"),
             ("kind", "link"),
             ("title", title),
             ("url", link),
             ("sr", sr),
         ];
 
-        self.post("api/submit", &form).await
+        Ok(self
+            .post("api/submit", &form)
+            .await?
+            .json::<JsonResponse<Submitted>>()?
+            .json
+            .data)
     }
 
     /// Submit text
@@ -96,15 +206,23 @@ impl Me {
         title: &str,
         text: &str,
         sr: &str,
-    ) -> Result<Response, RouxError> {
+    ) -> Result<Submitted, RouxError> {
+        self.require(Scope::Submit)?;
+
         let form = [
+            ("api_type", "json"),
             ("kind", "self"),
             ("title", title),
             ("text", text),
             ("sr", sr),
         ];
 
-        self.post("api/submit", &form).await
+        Ok(self
+            .post("api/submit", &form)
+            .await?
+            .json::<JsonResponse<Submitted>>()?
+            .json
+            .data)
     }
 
     /// Adds a friend to a subreddit with the specified type
@@ -114,12 +232,15 @@ impl Me {
         typ: &str,
         sub: &str,
     ) -> Result<bool, RouxError> {
+        if let Some(scope) = friend_scope(typ) {
+            self.require(scope)?;
+        }
+
         let form = [("name", username), ("type", typ)];
         Ok(self
             .post(format!("r/{}/api/friend", sub).as_str(), form)
             .await?
-            .json::<Friend>()
-            .await?
+            .json::<Friend>()?
             .success)
     }
 
@@ -130,12 +251,15 @@ impl Me {
         typ: &str,
         sub: &str,
     ) -> Result<bool, RouxError> {
+        if let Some(scope) = friend_scope(typ) {
+            self.require(scope)?;
+        }
+
         let form = [("name", username), ("type", typ)];
         Ok(self
             .post(format!("r/{}/api/unfriend", sub).as_str(), form)
             .await?
-            .json::<Friend>()
-            .await?
+            .json::<Friend>()?
             .success)
     }
 
@@ -145,7 +269,9 @@ impl Me {
         username: &str,
         subject: &str,
         body: &str,
-    ) -> Result<Response, RouxError> {
+    ) -> Result<(), RouxError> {
+        self.require(Scope::PrivateMessages)?;
+
         let form = [
             ("api_type", "json"),
             ("subject", subject),
@@ -153,91 +279,747 @@ impl Me {
             ("to", username),
         ];
 
-        self.post("api/compose", &form).await
+        self.post("api/compose", &form).await?;
+        Ok(())
+    }
+
+    /// Get the descriptions of every OAuth scope.
+    pub async fn available_scopes(&self) -> Result<Scopes, RouxError> {
+        self.get("api/v1/scopes").await?.json::<Scopes>()
+    }
+
+    fn username(&self) -> Result<&str, RouxError> {
+        self.auth
+            .config
+            .username
+            .as_deref()
+            .ok_or(RouxError::Credentials("username"))
+    }
+
+    async fn get_listing<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        limit: Option<u32>,
+        options: Option<FeedOption>,
+    ) -> Result<T, RouxError> {
+        let url = &mut self.auth.config.endpoints.build_oauth(path);
+        url.push('?');
+        push_options(url, limit, options)?;
+
+        self.auth.send(false, Request::get(url)).await?.json::<T>()
     }
 
-    /// Get user's submitted posts.
+    /// Get user's inbox.
     pub async fn inbox(&self) -> Result<Inbox, RouxError> {
-        Ok(self.get("message/inbox").await?.json::<Inbox>().await?)
+        self.require(Scope::PrivateMessages)?;
+
+        self.get_listing("message/inbox", None, None).await
     }
 
     /// Get saved
     pub async fn saved(&self) -> Result<Submissions, RouxError> {
-        let url = format!(
-            "user/{}/saved/.json",
-            self.config.username.to_owned().unwrap()
-        );
+        self.require(Scope::History)?;
 
-        Ok(self.get(&url).await?.json::<Submissions>().await?)
+        let path = format!("user/{}/saved", self.username()?);
+        self.get_listing(&path, None, None).await
     }
 
     /// Get upvoted
     pub async fn upvoted(&self) -> Result<Submissions, RouxError> {
-        let url = format!(
-            "user/{}/upvoted/.json",
-            self.config.username.to_owned().unwrap()
-        );
+        self.require(Scope::History)?;
 
-        Ok(self.get(&url).await?.json::<Submissions>().await?)
+        let path = format!("user/{}/upvoted", self.username()?);
+        self.get_listing(&path, None, None).await
     }
 
     /// Get downvoted
     pub async fn downvoted(&self) -> Result<Submissions, RouxError> {
-        let url = format!(
-            "user/{}/downvoted/.json",
-            self.config.username.to_owned().unwrap()
-        );
+        self.require(Scope::History)?;
 
-        Ok(self.get(&url).await?.json::<Submissions>().await?)
+        let path = format!("user/{}/downvoted", self.username()?);
+        self.get_listing(&path, None, None).await
     }
 
     /// Get users unread messages
     pub async fn unread(&self) -> Result<Inbox, RouxError> {
-        Ok(self.get("message/unread").await?.json::<Inbox>().await?)
+        self.require(Scope::PrivateMessages)?;
+
+        self.get_listing("message/unread", None, None).await
+    }
+
+    /// Stream user's inbox, following `after` from page to page, until `cap` messages have
+    /// been returned or the listing ends.
+    pub fn inbox_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Stream<Item = Result<InboxData, RouxError>> + '_ {
+        self.paginated(Scope::PrivateMessages, options, cap, |_| {
+            Ok("message/inbox".to_owned())
+        })
+    }
+
+    /// Stream saved items, following `after` from page to page, until `cap` items have been
+    /// returned or the listing ends.
+    pub fn saved_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Stream<Item = Result<SubmissionsData, RouxError>> + '_ {
+        self.paginated(Scope::History, options, cap, |me| {
+            Ok(format!("user/{}/saved", me.username()?))
+        })
+    }
+
+    /// Stream upvoted posts, following `after` from page to page, until `cap` posts have
+    /// been returned or the listing ends.
+    pub fn upvoted_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Stream<Item = Result<SubmissionsData, RouxError>> + '_ {
+        self.paginated(Scope::History, options, cap, |me| {
+            Ok(format!("user/{}/upvoted", me.username()?))
+        })
+    }
+
+    /// Stream downvoted posts, following `after` from page to page, until `cap` posts have
+    /// been returned or the listing ends.
+    pub fn downvoted_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Stream<Item = Result<SubmissionsData, RouxError>> + '_ {
+        self.paginated(Scope::History, options, cap, |me| {
+            Ok(format!("user/{}/downvoted", me.username()?))
+        })
+    }
+
+    /// Pages through the listing at the path returned by `path`, which needs `scope`.
+    fn paginated<T: DeserializeOwned + 'static>(
+        &self,
+        scope: Scope,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+        path: fn(&Me) -> Result<String, RouxError>,
+    ) -> impl Stream<Item = Result<T, RouxError>> + '_ {
+        paginate(options, cap, move |limit, options| {
+            let scope = scope.clone();
+
+            async move {
+                self.require(scope)?;
+                let path = path(self)?;
+
+                self.get_listing(&path, Some(limit), Some(options)).await
+            }
+        })
+    }
+
+    /// Stream new messages, comment replies and username mentions as they arrive, polling
+    /// the unread messages and returning each item once, oldest first. Items are not marked
+    /// as read. The stream never ends; errors are returned and polling continues.
+    pub fn stream_inbox(
+        &self,
+        options: Option<PollOptions>,
+    ) -> impl Stream<Item = Result<InboxData, RouxError>> + '_ {
+        poll(
+            options,
+            |item: &InboxData| (item.created_utc, Some(&item.name)),
+            move |limit| async move {
+                self.require(Scope::PrivateMessages)?;
+
+                self.get_listing("message/unread", Some(limit), None).await
+            },
+        )
+    }
+
+    /// Call `handler` with each new item of `stream_inbox`, and mark the item as read once
+    /// `handler` succeeds if `mark_read` is set. Items are marked as read with one request
+    /// per poll, and ones that fail to be marked are tried again with the next poll.
+    ///
+    /// Runs until `handler` fails, returning its error. Polls that fail in a way the
+    /// `RetryPolicy` would retry, such as a `5xx` or a rate limit, are skipped and polling
+    /// backs off until Reddit recovers. Other errors, such as a missing scope, are returned.
+    pub async fn handle_inbox<F, Fut, E>(
+        &self,
+        options: Option<PollOptions>,
+        mark_read: bool,
+        mut handler: F,
+    ) -> Result<(), E>
+    where
+        F: FnMut(InboxData) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: From<RouxError>,
+    {
+        // Items of the same poll are ready together, so each chunk is one poll.
+        let mut polls = Box::pin(self.stream_inbox(options).ready_chunks(100));
+        let mut read = Vec::new();
+
+        while let Some(items) = polls.next().await {
+            let mut result = Ok(());
+
+            for item in items {
+                let item = match item {
+                    Ok(item) => item,
+                    Err(error) if error.is_retryable() => continue,
+                    Err(error) => {
+                        result = Err(error.into());
+                        break;
+                    }
+                };
+                let name = item.name.clone();
+
+                result = handler(item).await;
+                if result.is_err() {
+                    break;
+                }
+
+                if mark_read {
+                    read.push(name);
+                }
+            }
+
+            if !read.is_empty() && self.mark_read(&read.join(",")).await.is_ok() {
+                read.clear();
+            }
+
+            result?;
+        }
+
+        Ok(())
     }
 
     /// Mark messages as read
     pub async fn mark_read(&self, ids: &str) -> Result<Response, RouxError> {
+        self.require(Scope::PrivateMessages)?;
+
         let form = [("id", ids)];
         self.post("api/read_message", &form).await
     }
 
     /// Mark messages as unread
     pub async fn mark_unread(&self, ids: &str) -> Result<Response, RouxError> {
+        self.require(Scope::PrivateMessages)?;
+
         let form = [("id", ids)];
         self.post("api/unread_message", &form).await
     }
 
-    /// Comment
-    pub async fn comment(&self, text: &str, parent: &str) -> Result<Response, RouxError> {
-        let form = [("text", text), ("parent", parent)];
-        self.post("api/comment", &form).await
+    /// Comment on the submission or comment with the fullname `parent`, returning the new
+    /// comment.
+    pub async fn comment(
+        &self,
+        text: &str,
+        parent: &str,
+    ) -> Result<SubredditCommentsData, RouxError> {
+        self.require(Scope::Submit)?;
+
+        let form = [("api_type", "json"), ("text", text), ("parent", parent)];
+        self.post_thing("api/comment", &form).await
     }
 
-    /// Edit a 'thing'
-    pub async fn edit(&self, text: &str, parent: &str) -> Result<Response, RouxError> {
-        let form = [("text", text), ("thing_id", parent)];
-        self.post("api/editusertext", &form).await
+    /// Edit the text of the comment with the fullname `parent`, returning the edited comment.
+    pub async fn edit(&self, text: &str, parent: &str) -> Result<SubredditCommentsData, RouxError> {
+        self.require(Scope::Edit)?;
+
+        let form = [("api_type", "json"), ("text", text), ("thing_id", parent)];
+        self.post_thing("api/editusertext", &form).await
     }
 
     /// Logout
+    ///
+    /// Revokes the refresh token if there is one, which also revokes every access token
+    /// issued with it, otherwise the access token.
     pub async fn logout(self) -> Result<(), RouxError> {
-        let url = "https://www.reddit.com/api/v1/revoke_token";
+        let url = self.auth.config.endpoints.build_auth("api/v1/revoke_token");
 
-        let form = [("access_token", self.access_token.to_owned())];
+        let form = match self.refresh_token() {
+            Some(refresh_token) => [
+                ("token", refresh_token),
+                ("token_type_hint", "refresh_token".to_owned()),
+            ],
+            None => [
+                ("token", self.access_token()),
+                ("token_type_hint", "access_token".to_owned()),
+            ],
+        };
 
-        let response = self
-            .client
-            .post(url)
-            .basic_auth(&self.config.client_id, Some(&self.config.client_secret))
-            .form(&form)
-            .send()
-            .await?;
+        let request = Request::post(&url)
+            .header("user-agent", &self.auth.config.user_agent)
+            .basic_auth(&self.auth.config.client_id, &self.auth.config.client_secret)
+            .form(&form)?;
 
-        if response.status() == 204 {
-            Ok(())
-        } else {
-            Err(RouxError::Status(response))
+        self.auth
+            .config
+            .transport
+            .send(request)
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
+
+impl Authenticated {
+    fn access_token(&self) -> String {
+        self.token.read().unwrap().access_token.to_owned()
+    }
+
+    fn can_refresh(&self) -> bool {
+        self.token.read().unwrap().refresh_token.is_some()
+            || matches!(self.config.grant, Some(ref grant) if grant.is_repeatable())
+    }
+
+    async fn refresh(&self) -> Result<(), RouxError> {
+        let _refreshing = self.refreshing.lock().await;
+        self.request_token().await
+    }
+
+    /// Renews the token unless another request already replaced `stale` while this one
+    /// waited for its turn.
+    async fn refresh_stale(&self, stale: &str) -> Result<(), RouxError> {
+        let _refreshing = self.refreshing.lock().await;
+
+        if self.access_token() != stale {
+            return Ok(());
+        }
+
+        self.request_token().await
+    }
+
+    async fn request_token(&self) -> Result<(), RouxError> {
+        let refresh_token = self.token.read().unwrap().refresh_token.to_owned();
+
+        let form = match (refresh_token, &self.config.grant) {
+            (Some(refresh_token), _) => vec![
+                ("grant_type", "refresh_token".to_owned()),
+                ("refresh_token", refresh_token),
+            ],
+            (None, Some(grant)) if grant.is_repeatable() => {
+                grant.form(&self.config).map_err(RouxError::Credentials)?
+            }
+            _ => return Err(RouxError::Credentials("refresh_token")),
+        };
+
+        let token = auth::request_token(&self.config, &form).await?;
+        self.token.write().unwrap().renew(token);
+
+        Ok(())
+    }
+
+    /// Sends a request with the access token, renewing it first if it is about to expire
+    /// and once more if Reddit rejects it.
+    pub(crate) async fn send(&self, write: bool, request: Request) -> Result<Response, RouxError> {
+        let expiring = self.token.read().unwrap().is_expiring();
+
+        if expiring && self.can_refresh() {
+            self.refresh_stale(&self.access_token()).await?;
+        }
+
+        let token = self.access_token();
+        let mut response = self.send_with_retries(write, &request, &token).await?;
+
+        if response.status == 401 && self.can_refresh() {
+            self.refresh_stale(&token).await?;
+            response = self
+                .send_with_retries(write, &request, &self.access_token())
+                .await?;
+        }
+
+        response.error_for_status()
+    }
+
+    async fn send_with_retries(
+        &self,
+        write: bool,
+        request: &Request,
+        token: &str,
+    ) -> Result<Response, RouxError> {
+        let request = request
+            .clone()
+            .header("user-agent", &self.config.user_agent)
+            .bearer_auth(token);
+
+        transport::execute(
+            &*self.config.transport,
+            &self.config.rate_limiter,
+            &self.config.retry_policy,
+            write,
+            &request,
+        )
+        .await
+    }
+}
+
+/// The scope needed to add or remove a subreddit relationship of type `typ`.
+fn friend_scope(typ: &str) -> Option<Scope> {
+    match typ {
+        "moderator" | "moderator_invite" => Some(Scope::ModOthers),
+        "contributor" | "banned" | "muted" => Some(Scope::ModContributors),
+        "wikibanned" | "wikicontributor" => Some(Scope::ModWiki),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::{Duration as StdDuration, SystemTime};
+
+    use futures_timer::Delay;
+
+    use serde_json::json;
+
+    use super::responses::InboxKind;
+    use super::Me;
+    use crate::auth::{Scope, Token};
+    use crate::config::{Config, Grant};
+    use crate::stream::PollOptions;
+    use crate::transport::{BoxFuture, Method, MockTransport, Request, Response, Transport};
+    use crate::util::{RetryPolicy, RouxError};
+
+    #[tokio::test]
+    async fn test_authenticated_subreddit() {
+        let mock = Arc::new(MockTransport::new());
+        mock.route(
+            Method::Get,
+            "https://oauth.reddit.com/r/rust/about/moderators",
+            Response::new(
+                200,
+                r#"{"kind": "Listing", "data": {"children": [{"kind": "t2", "data": {"id": "t2_1", "name": "mod"}}]}}"#,
+            ),
+        );
+
+        let mut config = Config::new("ua", "id", "secret");
+        config.transport = mock.clone();
+        let me = Me::new("access", config);
+
+        let moderators = me.subreddit("rust").moderators().await.unwrap();
+        assert_eq!(moderators.data.children[0].data.name, "mod");
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].header_value("authorization"),
+            Some("Bearer access")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_typed_write_responses() {
+        let mock = Arc::new(MockTransport::new());
+        mock.route(
+            Method::Post,
+            "api/submit",
+            Response::new(
+                200,
+                r#"{"json": {"errors": [], "data": {"url": "https://www.reddit.com/r/rust/comments/abc/title/", "drafts_count": 0, "id": "abc", "name": "t3_abc"}}}"#,
+            ),
+        );
+        mock.route(
+            Method::Post,
+            "api/comment",
+            Response::new(
+                200,
+                r#"{"json": {"errors": [], "data": {"things": [{"kind": "t1", "data": {"id": "def", "name": "t1_def", "body": "text", "parent_id": "t3_abc"}}]}}}"#,
+            ),
+        );
+
+        let mut config = Config::new("ua", "id", "secret");
+        config.transport = mock.clone();
+        let me = Me::new("access", config);
+
+        let submitted = me.submit_text("title", "text", "rust").await.unwrap();
+        assert_eq!(submitted.name, "t3_abc");
+
+        let comment = me.comment("text", &submitted.name).await.unwrap();
+        assert_eq!(comment.name, Some("t1_def".to_owned()));
+        assert_eq!(comment.parent_id, Some("t3_abc".to_owned()));
+
+        let requests = mock.requests();
+        assert!(requests[1].body.as_ref().unwrap().contains("parent=t3_abc"));
+    }
+
+    #[tokio::test]
+    async fn test_comment_without_things() {
+        let mock = Arc::new(MockTransport::new());
+        mock.route(
+            Method::Post,
+            "api/comment",
+            Response::new(200, r#"{"json": {"errors": [], "data": {"things": []}}}"#),
+        );
+
+        let mut config = Config::new("ua", "id", "secret");
+        config.transport = mock.clone();
+        let me = Me::new("access", config);
+
+        assert!(matches!(
+            me.comment("text", "t3_abc").await,
+            Err(RouxError::Parse(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_api_errors() {
+        let mock = Arc::new(MockTransport::new());
+        mock.route(
+            Method::Post,
+            "api/submit",
+            Response::new(
+                200,
+                r#"{"json": {"errors": [["SUBREDDIT_NOEXIST", "that subreddit doesn't exist", "sr"]]}}"#,
+            ),
+        );
+
+        let mut config = Config::new("ua", "id", "secret");
+        config.transport = mock.clone();
+        let me = Me::new("access", config);
+
+        match me.submit_text("title", "text", "nope").await {
+            Err(RouxError::Api(errors)) => assert_eq!(errors[0].code, "SUBREDDIT_NOEXIST"),
+            _ => panic!("expected an api error"),
+        }
+    }
+
+    /// Waits a moment before answering, so concurrent requests overlap.
+    struct Slow(MockTransport);
+
+    impl Transport for Slow {
+        fn send(&self, request: Request) -> BoxFuture<'_, Result<Response, RouxError>> {
+            Box::pin(async move {
+                Delay::new(StdDuration::from_millis(10)).await;
+                self.0.send(request).await
+            })
         }
     }
+
+    #[tokio::test]
+    async fn test_single_refresh() {
+        let mock = MockTransport::new();
+        mock.route(
+            Method::Post,
+            "api/v1/access_token",
+            Response::new(200, r#"{"access_token": "new", "expires_in": 3600}"#),
+        );
+        mock.route(Method::Get, "api/v1/me", Response::new(503, ""));
+        mock.route(Method::Get, "api/v1/me", Response::new(503, ""));
+        let slow = Arc::new(Slow(mock));
+
+        let mut token = Token::new("old");
+        token.refresh_token = Some("refresh".to_owned());
+        token.expires_at = Some(SystemTime::now() + StdDuration::from_secs(1));
+
+        let mut config = Config::new("ua", "id", "secret");
+        config.transport = slow.clone();
+        config.retry_policy = crate::util::RetryPolicy::never();
+        let me = Me::from_token(token, config);
+
+        let _ = futures_util::join!(me.me(), me.me());
+
+        let requests = slow.0.requests();
+        let refreshes = requests
+            .iter()
+            .filter(|request| request.url.contains("access_token"))
+            .count();
+        assert_eq!(refreshes, 1);
+        assert_eq!(requests.len(), 3);
+        assert_eq!(me.access_token(), "new");
+    }
+
+    #[tokio::test]
+    async fn test_refresh_after_unauthorized() {
+        let mock = Arc::new(MockTransport::new());
+        mock.route(Method::Get, "api/v1/me", Response::new(401, ""));
+        mock.route(
+            Method::Post,
+            "api/v1/access_token",
+            Response::new(200, r#"{"access_token": "new", "expires_in": 3600}"#),
+        );
+        mock.route(Method::Get, "api/v1/me", Response::new(503, ""));
+
+        let mut config = Config::new("ua", "id", "secret");
+        config.transport = mock.clone();
+        config.grant = Some(Grant::ClientCredentials);
+        config.retry_policy = crate::util::RetryPolicy::never();
+        let me = Me::new("old", config);
+
+        assert!(me.me().await.is_err());
+        assert_eq!(me.access_token(), "new");
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(
+            requests[0].header_value("authorization"),
+            Some("Bearer old")
+        );
+        assert_eq!(
+            requests[2].header_value("authorization"),
+            Some("Bearer new")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_scope() {
+        let mut token = Token::new("access");
+        token.scopes = Some(vec![Scope::Identity, Scope::Read]);
+        let me = Me::from_token(token, Config::new("ua", "id", "secret"));
+
+        assert!(me.has_scope(Scope::Read));
+        assert!(!me.has_scope(Scope::Submit));
+
+        match me.submit_text("title", "text", "rust").await {
+            Err(RouxError::MissingScope(Scope::Submit)) => {}
+            _ => panic!("expected a missing scope error"),
+        }
+    }
+
+    fn inbox_item(id: &str, kind: &str, created_utc: f64) -> serde_json::Value {
+        json!({
+                "kind": "t1",
+                "data": {
+                    "id": id,
+                    "name": format!("t1_{}", id),
+                    "subject": "subject",
+                    "was_comment": true,
+                    "new": true,
+                    "type": kind,
+                    "body": "body",
+                    "dest": "me",
+                    "body_html": "body",
+                    "created": created_utc,
+                    "created_utc": created_utc,
+                "context": "",
+            },
+        })
+    }
+
+    #[tokio::test]
+    async fn test_handle_inbox() {
+        let unread = json!({
+            "kind": "Listing",
+            "data": {
+                "children": [
+                    inbox_item("b", "username_mention", 2.0),
+                    inbox_item("a", "comment_reply", 1.0),
+                ],
+            },
+        });
+
+        let mock = Arc::new(MockTransport::new());
+        mock.route(
+            Method::Get,
+            "message/unread",
+            Response::new(200, &unread.to_string()),
+        );
+        mock.route(Method::Post, "api/read_message", Response::new(200, "{}"));
+
+        let mut config = Config::new("ua", "id", "secret");
+        config.transport = mock.clone();
+        let me = Me::new("access", config);
+
+        let mut kinds = Vec::new();
+        let result = me
+            .handle_inbox(None, true, |item| {
+                kinds.push(item.kind());
+
+                let result = match item.kind() {
+                    InboxKind::CommentReply => Ok(()),
+                    _ => Err(RouxError::NotFound(Response::new(404, ""))),
+                };
+                async move { result }
+            })
+            .await;
+
+        assert!(matches!(result, Err(RouxError::NotFound(_))));
+        assert_eq!(kinds, [InboxKind::CommentReply, InboxKind::UsernameMention]);
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].body, Some("id=t1_a".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn test_handle_inbox_keeps_polling() {
+        let unread = json!({
+            "kind": "Listing",
+            "data": {
+                "children": [
+                    inbox_item("c", "comment_reply", 3.0),
+                    inbox_item("b", "comment_reply", 2.0),
+                    inbox_item("a", "comment_reply", 1.0),
+                ],
+            },
+        });
+
+        let mock = Arc::new(MockTransport::new());
+        mock.route(Method::Get, "message/unread", Response::new(503, ""));
+        mock.route(
+            Method::Get,
+            "message/unread",
+            Response::new(200, &unread.to_string()),
+        );
+        mock.route(Method::Post, "api/read_message", Response::new(200, "{}"));
+
+        let mut config = Config::new("ua", "id", "secret");
+        config.transport = mock.clone();
+        config.retry_policy = RetryPolicy::never();
+        let me = Me::new("access", config);
+
+        let options = PollOptions::new().min_interval(StdDuration::from_millis(1));
+        let mut handled = 0;
+        let result = me
+            .handle_inbox(Some(options), true, |_| {
+                handled += 1;
+
+                let result = match handled {
+                    3 => Err(RouxError::InvalidOption("stop")),
+                    _ => Ok(()),
+                };
+                async move { result }
+            })
+            .await;
+
+        assert!(matches!(result, Err(RouxError::InvalidOption("stop"))));
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[2].body, Some("id=t1_a%2Ct1_b".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn test_handle_inbox_missing_scope() {
+        let mock = Arc::new(MockTransport::new());
+        let mut token = Token::new("access");
+        token.scopes = Some(vec![Scope::Identity]);
+
+        let mut config = Config::new("ua", "id", "secret");
+        config.transport = mock.clone();
+        let me = Me::from_token(token, config);
+
+        let options = PollOptions::new().min_interval(StdDuration::from_millis(1));
+        let result = me
+            .handle_inbox(Some(options), true, |_| async { Ok::<_, RouxError>(()) })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(RouxError::MissingScope(Scope::PrivateMessages))
+        ));
+        assert!(mock.requests().is_empty());
+    }
+
+    #[test]
+    fn test_unknown_scopes() {
+        let me = Me::new("access", Config::new("ua", "id", "secret"));
+        assert!(me.has_scope(Scope::ModPosts));
+    }
+
+    #[test]
+    fn test_no_scopes() {
+        let mut token = Token::new("access");
+        token.scopes = Some(Vec::new());
+        let me = Me::from_token(token, Config::new("ua", "id", "secret"));
+
+        assert!(!me.has_scope(Scope::Read));
+    }
 }
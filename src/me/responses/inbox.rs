@@ -38,5 +38,36 @@ pub struct InboxData {
     pub context: String,
 }
 
+/// What an inbox item is.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InboxKind {
+    /// A private message.
+    Message,
+    /// A reply to one of the user's comments.
+    CommentReply,
+    /// A reply to one of the user's posts.
+    PostReply,
+    /// A comment mentioning `/u/username`.
+    UsernameMention,
+    /// Anything else, such as a moderator mail notification.
+    Other,
+}
+
+impl InboxData {
+    /// Get what this item is.
+    pub fn kind(&self) -> InboxKind {
+        if !self.was_comment {
+            return InboxKind::Message;
+        }
+
+        match &self.r#type[..] {
+            "comment_reply" => InboxKind::CommentReply,
+            "post_reply" => InboxKind::PostReply,
+            "username_mention" => InboxKind::UsernameMention,
+            _ => InboxKind::Other,
+        }
+    }
+}
+
 /// Inbox
 pub type Inbox = BasicListing<InboxData>;
@@ -3,4 +3,11 @@ pub mod me;
 pub use me::{Friend, MeData};
 
 pub mod inbox;
-pub use inbox::{Inbox, InboxData};
+pub use inbox::{Inbox, InboxData, InboxKind};
+
+pub mod scopes;
+pub use scopes::{ScopeData, Scopes};
+
+pub mod submit;
+pub use submit::Submitted;
+pub(crate) use submit::{JsonResponse, Things};
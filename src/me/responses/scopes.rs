@@ -0,0 +1,18 @@
+//! # Scope Responses
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+/// ScopeData
+#[derive(Debug, Deserialize)]
+pub struct ScopeData {
+    /// ID, as used when requesting the scope.
+    pub id: String,
+    /// Name
+    pub name: String,
+    /// Description
+    pub description: String,
+}
+
+/// Scopes, keyed by ID.
+pub type Scopes = HashMap<String, ScopeData>;
@@ -0,0 +1,32 @@
+//! # Submit Responses
+use serde::Deserialize;
+
+use crate::responses::BasicThing;
+
+/// A submission created with `Me::submit_link` or `Me::submit_text`.
+#[derive(Debug, Deserialize)]
+pub struct Submitted {
+    /// ID, e.g. `abc123`.
+    pub id: String,
+    /// Fullname, e.g. `t3_abc123`.
+    pub name: String,
+    /// URL of the submission.
+    pub url: String,
+}
+
+/// The `{"json": {"data": ...}}` wrapper around responses of `api_type=json` requests.
+#[derive(Debug, Deserialize)]
+pub(crate) struct JsonResponse<T> {
+    pub json: JsonData<T>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct JsonData<T> {
+    pub data: T,
+}
+
+/// Things created or changed by a request, such as a new comment.
+#[derive(Debug, Deserialize)]
+pub(crate) struct Things<T> {
+    pub things: Vec<BasicThing<T>>,
+}
@@ -0,0 +1,26 @@
+//! # Streams
+//! Streams that page through listings, or poll them for new items, for you.
+//!
+//! ```no_run
+//! use futures_util::TryStreamExt;
+//! use roux::Subreddit;
+//! # use tokio_test;
+//!
+//! # tokio_test::block_on(async {
+//! let subreddit = Subreddit::new("rust");
+//!
+//! // The last 1000 posts, fetched 100 at a time.
+//! let posts: Vec<_> = subreddit
+//!     .latest_paginated(None, Some(1000))
+//!     .try_collect()
+//!     .await
+//!     .unwrap();
+//! # })
+//! ```
+
+mod paginate;
+pub use paginate::{paginate, PAGE_SIZE};
+
+mod poll;
+pub(crate) use poll::poll;
+pub use poll::PollOptions;
@@ -0,0 +1,270 @@
+use std::collections::VecDeque;
+use std::future::Future;
+
+use futures_util::stream::{self, Stream};
+
+use crate::responses::BasicListing;
+use crate::util::{FeedOption, RouxError};
+
+/// The most items Reddit returns in one page.
+pub const PAGE_SIZE: u32 = 100;
+
+struct State<T, F> {
+    fetch: F,
+    page_size: u32,
+    options: FeedOption,
+    backwards: bool,
+    count: usize,
+    cap: Option<usize>,
+    buffer: VecDeque<T>,
+    seen: usize,
+    done: bool,
+}
+
+/// Streams every item of a listing, fetching a page with `fetch(limit, options)` whenever
+/// the previous one has been used up and following its `after` to the next one. Stops after
+/// `cap` items if given, at the end of the listing, or after the first error.
+///
+/// `options` are used for the first page, so a starting point or time period can be set.
+/// If `before` is set the listing is followed backwards, page by page, using `before`
+/// instead. A `count` is taken as the number of items already seen before the first page,
+/// and a `limit` as the size of each page instead of the largest Reddit allows.
+///
+/// ```no_run
+/// use futures_util::TryStreamExt;
+/// use roux::Subreddit;
+/// use roux::stream::paginate;
+/// # use tokio_test;
+///
+/// # tokio_test::block_on(async {
+/// let subreddit = Subreddit::new("rust");
+///
+/// let posts: Vec<_> = paginate(None, Some(250), |limit, options| {
+///     subreddit.top(limit, Some(options))
+/// })
+/// .try_collect()
+/// .await
+/// .unwrap();
+/// # })
+/// ```
+pub fn paginate<T, F, Fut>(
+    options: Option<FeedOption>,
+    cap: Option<usize>,
+    fetch: F,
+) -> impl Stream<Item = Result<T, RouxError>>
+where
+    F: FnMut(u32, FeedOption) -> Fut,
+    Fut: Future<Output = Result<BasicListing<T>, RouxError>>,
+{
+    let mut options = options.unwrap_or_default();
+    let page_size = options
+        .limit
+        .take()
+        .map_or(PAGE_SIZE, |limit| limit.clamp(1, PAGE_SIZE));
+
+    let state = State {
+        fetch,
+        page_size,
+        backwards: options.before.is_some(),
+        count: options.count.unwrap_or(0) as usize,
+        options,
+        cap,
+        buffer: VecDeque::new(),
+        seen: 0,
+        done: false,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        loop {
+            if matches!(state.cap, Some(cap) if state.seen >= cap) {
+                return None;
+            }
+
+            if let Some(item) = state.buffer.pop_front() {
+                state.seen += 1;
+                return Some((Ok(item), state));
+            }
+
+            if state.done {
+                return None;
+            }
+
+            let limit = match state.cap {
+                Some(cap) => (cap - state.seen).min(state.page_size as usize) as u32,
+                None => state.page_size,
+            };
+
+            let mut options = state.options.clone();
+            if state.seen > 0 {
+                options.count = Some((state.count + state.seen) as u32);
+            }
+
+            match (state.fetch)(limit, options).await {
+                Ok(listing) => {
+                    let listing = listing.data;
+
+                    let next = if state.backwards {
+                        state.options.before = listing.before;
+                        &state.options.before
+                    } else {
+                        state.options.after = listing.after;
+                        &state.options.after
+                    };
+
+                    state.done = next.is_none() || listing.children.is_empty();
+                    state
+                        .buffer
+                        .extend(listing.children.into_iter().map(|child| child.data));
+                }
+                Err(e) => {
+                    state.done = true;
+                    return Some((Err(e), state));
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::TryStreamExt;
+
+    use super::paginate;
+    use crate::responses::{BasicListing, BasicThing, Listing};
+    use crate::transport::Response;
+    use crate::util::{FeedOption, RouxError};
+
+    fn page(items: Vec<u32>, after: Option<&str>) -> BasicListing<u32> {
+        BasicThing {
+            kind: "Listing".to_owned(),
+            data: Listing {
+                modhash: None,
+                dist: None,
+                after: after.map(str::to_owned),
+                before: None,
+                children: items
+                    .into_iter()
+                    .map(|data| BasicThing {
+                        kind: "t3".to_owned(),
+                        data,
+                    })
+                    .collect(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_follows_after() {
+        let mut calls: Vec<(u32, FeedOption)> = Vec::new();
+
+        let items: Vec<u32> = paginate(None, None, |limit, options: FeedOption| {
+            calls.push((limit, options.clone()));
+            let result = match options.after.as_deref() {
+                None => Ok(page(vec![1, 2], Some("t3_2"))),
+                Some("t3_2") => Ok(page(vec![3], None)),
+                _ => Err(RouxError::NotFound(Response::new(404, ""))),
+            };
+            async move { result }
+        })
+        .try_collect()
+        .await
+        .unwrap();
+
+        assert_eq!(items, [1, 2, 3]);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[1].1.count, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_cap() {
+        let mut limits = Vec::new();
+
+        let items: Vec<u32> = paginate(None, Some(3), |limit, _| {
+            limits.push(limit);
+            async move { Ok(page(vec![1, 2], Some("t3_2"))) }
+        })
+        .try_collect()
+        .await
+        .unwrap();
+
+        assert_eq!(items, [1, 2, 1]);
+        assert_eq!(limits, [3, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_follows_before() {
+        let mut calls: Vec<FeedOption> = Vec::new();
+
+        let items: Vec<u32> = paginate(
+            Some(FeedOption::new().before("t3_9").count(50)),
+            None,
+            |_, options: FeedOption| {
+                calls.push(options.clone());
+                let mut listing = page(vec![1, 2], None);
+                if options.before.as_deref() == Some("t3_9") {
+                    listing.data.before = Some("t3_1".to_owned());
+                }
+                async move { Ok(listing) }
+            },
+        )
+        .try_collect()
+        .await
+        .unwrap();
+
+        assert_eq!(items, [1, 2, 1, 2]);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].count, Some(50));
+        assert_eq!(calls[1].before.as_deref(), Some("t3_1"));
+        assert_eq!(calls[1].after, None);
+        assert_eq!(calls[1].count, Some(52));
+    }
+
+    #[tokio::test]
+    async fn test_large_cap() {
+        let mut limits = Vec::new();
+
+        let items: Vec<u32> = paginate(None, Some(usize::MAX), |limit, _| {
+            limits.push(limit);
+            async move { Ok(page(vec![1], None)) }
+        })
+        .try_collect()
+        .await
+        .unwrap();
+
+        assert_eq!(items, [1]);
+        assert_eq!(limits, [100]);
+    }
+
+    #[tokio::test]
+    async fn test_page_size() {
+        let mut calls: Vec<(u32, FeedOption)> = Vec::new();
+
+        let items: Vec<u32> = paginate(
+            Some(FeedOption::new().limit(2)),
+            Some(3),
+            |limit, options: FeedOption| {
+                calls.push((limit, options));
+                async move { Ok(page(vec![1, 2], Some("t3_2"))) }
+            },
+        )
+        .try_collect()
+        .await
+        .unwrap();
+
+        assert_eq!(items, [1, 2, 1]);
+        assert_eq!(calls[0].0, 2);
+        assert_eq!(calls[1].0, 1);
+        assert!(calls.iter().all(|(_, options)| options.limit.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_stops_after_error() {
+        let items: Vec<Result<u32, RouxError>> =
+            futures_util::StreamExt::collect(paginate(None, None, |_, _| async {
+                Err::<BasicListing<u32>, _>(RouxError::NotFound(Response::new(404, "")))
+            }))
+            .await;
+
+        assert_eq!(items.len(), 1);
+    }
+}
@@ -0,0 +1,335 @@
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+use std::time::Duration;
+
+use futures_timer::Delay;
+use futures_util::stream::{self, Stream};
+
+use crate::responses::BasicListing;
+use crate::util::RouxError;
+
+/// How many fullnames are remembered to skip items that have already been returned.
+const HISTORY: usize = 1000;
+
+/// How a live stream polls Reddit.
+///
+/// The interval drops to `min_interval` whenever a poll finds something new and doubles,
+/// up to `max_interval`, after each poll that doesn't, so quiet subreddits are polled less.
+#[derive(Clone, Debug)]
+pub struct PollOptions {
+    min_interval: Duration,
+    max_interval: Duration,
+    limit: u32,
+    skip_existing: bool,
+}
+
+impl PollOptions {
+    /// Create a new `PollOptions` instance that polls every 5 to 60 seconds for the latest
+    /// 100 items, and starts by returning the items that already exist.
+    pub fn new() -> PollOptions {
+        PollOptions {
+            min_interval: Duration::from_secs(5),
+            max_interval: Duration::from_secs(60),
+            limit: 100,
+            skip_existing: false,
+        }
+    }
+
+    /// Set the shortest time between two polls.
+    pub fn min_interval(mut self, min_interval: Duration) -> PollOptions {
+        self.min_interval = min_interval;
+        self
+    }
+
+    /// Set the longest time between two polls.
+    pub fn max_interval(mut self, max_interval: Duration) -> PollOptions {
+        self.max_interval = max_interval;
+        self
+    }
+
+    /// Set how many of the latest items are requested in each poll, at most 100.
+    pub fn limit(mut self, limit: u32) -> PollOptions {
+        self.limit = limit.clamp(1, 100);
+        self
+    }
+
+    /// Set whether the items that exist when the stream starts are skipped, so only items
+    /// created afterwards are returned.
+    pub fn skip_existing(mut self, skip_existing: bool) -> PollOptions {
+        self.skip_existing = skip_existing;
+        self
+    }
+}
+
+impl Default for PollOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Remembers the most recent fullnames.
+struct History {
+    set: HashSet<String>,
+    order: VecDeque<String>,
+}
+
+impl History {
+    fn new() -> History {
+        History {
+            set: HashSet::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Adds `fullname`, returning whether it is new.
+    fn insert(&mut self, fullname: &str) -> bool {
+        if self.set.contains(fullname) {
+            return false;
+        }
+
+        if self.order.len() == HISTORY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.set.remove(&oldest);
+            }
+        }
+
+        self.set.insert(fullname.to_owned());
+        self.order.push_back(fullname.to_owned());
+        true
+    }
+}
+
+struct State<T, F> {
+    fetch: F,
+    options: PollOptions,
+    key: fn(&T) -> (f64, Option<&str>),
+    history: History,
+    buffer: VecDeque<T>,
+    interval: Duration,
+    /// Whether a poll has been attempted, so the next one waits for the interval.
+    started: bool,
+    /// Whether a poll has succeeded, so the items that existed at the start are known.
+    polled: bool,
+}
+
+/// Polls a listing of the latest items with `fetch(limit)` forever, returning each item
+/// once, oldest first. `key` gives the creation time and fullname of an item. Items without
+/// a fullname can't be told apart, so they are returned every time they are seen.
+///
+/// Errors are returned as they happen and count as a poll without new items, so the stream
+/// keeps going and backs off until Reddit recovers.
+pub(crate) fn poll<T, F, Fut>(
+    options: Option<PollOptions>,
+    key: fn(&T) -> (f64, Option<&str>),
+    fetch: F,
+) -> impl Stream<Item = Result<T, RouxError>>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<BasicListing<T>, RouxError>>,
+{
+    let options = options.unwrap_or_default();
+
+    let state = State {
+        fetch,
+        interval: options.min_interval,
+        options,
+        key,
+        history: History::new(),
+        buffer: VecDeque::new(),
+        started: false,
+        polled: false,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(item) = state.buffer.pop_front() {
+                return Some((Ok(item), state));
+            }
+
+            if state.started {
+                Delay::new(state.interval).await;
+            }
+            state.started = true;
+
+            let listing = match (state.fetch)(state.options.limit).await {
+                Ok(listing) => listing,
+                Err(e) => {
+                    state.interval = (state.interval * 2).min(state.options.max_interval);
+                    return Some((Err(e), state));
+                }
+            };
+
+            let first = !state.polled;
+            state.polled = true;
+
+            let key = state.key;
+            let history = &mut state.history;
+            let mut items: Vec<T> = listing
+                .data
+                .children
+                .into_iter()
+                .map(|child| child.data)
+                .filter(|item| match key(item).1 {
+                    Some(name) => history.insert(name),
+                    None => true,
+                })
+                .collect();
+
+            if first && state.options.skip_existing {
+                items.clear();
+            }
+
+            // Listings are newest first, so reverse before sorting to keep that order for
+            // items created in the same second.
+            items.reverse();
+            items.sort_by(|a, b| key(a).0.total_cmp(&key(b).0));
+
+            state.interval = if items.is_empty() {
+                (state.interval * 2).min(state.options.max_interval)
+            } else {
+                state.options.min_interval
+            };
+
+            state.buffer.extend(items);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures_util::StreamExt;
+
+    use super::{poll, History, PollOptions};
+    use crate::responses::{BasicListing, BasicThing, Listing};
+    use crate::transport::Response;
+    use crate::util::RouxError;
+
+    type Item = (f64, String);
+
+    fn page(items: &[(f64, &str)]) -> Result<BasicListing<Item>, RouxError> {
+        Ok(BasicThing {
+            kind: "Listing".to_owned(),
+            data: Listing {
+                modhash: None,
+                dist: None,
+                after: None,
+                before: None,
+                children: items
+                    .iter()
+                    .map(|(created, name)| BasicThing {
+                        kind: "t3".to_owned(),
+                        data: (*created, name.to_string()),
+                    })
+                    .collect(),
+            },
+        })
+    }
+
+    fn key(item: &Item) -> (f64, Option<&str>) {
+        (item.0, Some(&item.1))
+    }
+
+    fn options() -> Option<PollOptions> {
+        Some(
+            PollOptions::new()
+                .min_interval(Duration::from_millis(1))
+                .max_interval(Duration::from_millis(2)),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_dedupes_in_creation_order() {
+        let mut polls = vec![
+            page(&[(3.0, "c"), (2.0, "b"), (1.0, "a")]),
+            page(&[(4.0, "d"), (3.0, "c"), (2.0, "b")]),
+            Err(RouxError::NotFound(Response::new(404, ""))),
+            page(&[(5.0, "e"), (4.0, "d")]),
+        ]
+        .into_iter();
+
+        let items: Vec<_> = poll(options(), key, |_| {
+            let result = polls.next().unwrap_or_else(|| page(&[]));
+            async move { result }
+        })
+        .take(6)
+        .collect()
+        .await;
+
+        let names: Vec<_> = items
+            .iter()
+            .map(|item| item.as_ref().map(|item| &item.1[..]).unwrap_or("error"))
+            .collect();
+        assert_eq!(names, ["a", "b", "c", "d", "error", "e"]);
+    }
+
+    #[tokio::test]
+    async fn test_skip_existing() {
+        let mut polls = vec![
+            page(&[(2.0, "b"), (1.0, "a")]),
+            page(&[(3.0, "c"), (2.0, "b")]),
+        ]
+        .into_iter();
+
+        let options = options().map(|options| options.skip_existing(true));
+        let items: Vec<_> = poll(options, key, |_| {
+            let result = polls.next().unwrap_or_else(|| page(&[]));
+            async move { result }
+        })
+        .take(1)
+        .collect()
+        .await;
+
+        assert_eq!(items[0].as_ref().unwrap().1, "c");
+    }
+
+    #[tokio::test]
+    async fn test_skip_existing_after_error() {
+        let mut polls = vec![
+            Err(RouxError::NotFound(Response::new(404, ""))),
+            page(&[(2.0, "b"), (1.0, "a")]),
+            page(&[(3.0, "c"), (2.0, "b")]),
+        ]
+        .into_iter();
+
+        let options = options().map(|options| options.skip_existing(true));
+        let items: Vec<_> = poll(options, key, |_| {
+            let result = polls.next().unwrap_or_else(|| page(&[]));
+            async move { result }
+        })
+        .take(2)
+        .collect()
+        .await;
+
+        assert!(items[0].is_err());
+        assert_eq!(items[1].as_ref().unwrap().1, "c");
+    }
+
+    #[tokio::test]
+    async fn test_items_without_fullname() {
+        fn no_fullname(item: &Item) -> (f64, Option<&str>) {
+            (item.0, None)
+        }
+
+        let items: Vec<_> = poll(options(), no_fullname, |_| async { page(&[(1.0, "a")]) })
+            .take(2)
+            .collect()
+            .await;
+
+        assert!(items.iter().all(|item| item.as_ref().unwrap().1 == "a"));
+        assert_eq!(items.len(), 2);
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = History::new();
+
+        for i in 0..super::HISTORY + 1 {
+            assert!(history.insert(&i.to_string()));
+        }
+
+        assert!(!history.insert(&super::HISTORY.to_string()));
+        assert!(history.insert("0"));
+    }
+}
@@ -0,0 +1,79 @@
+//! JSON builders for the subreddit tests.
+use serde_json::{json, Value};
+
+pub(crate) fn listing(children: Vec<Value>, after: Option<&str>) -> Value {
+    json!({
+        "kind": "Listing",
+        "data": { "after": after, "before": null, "children": children },
+    })
+}
+
+pub(crate) fn submission(id: &str, created_utc: f64) -> Value {
+    json!({
+        "kind": "t3",
+        "data": {
+            "domain": "self.rust",
+            "subreddit": "rust",
+            "selftext": "",
+            "id": id,
+            "gilded": 0,
+            "archived": false,
+            "clicked": false,
+            "author": "author",
+            "score": 1.0,
+            "over_18": false,
+            "hidden": false,
+            "num_comments": 0,
+            "thumbnail": "self",
+            "subreddit_id": "t5_2s7lj",
+            "hide_score": false,
+            "edited": false,
+            "downs": 0.0,
+            "ups": 1.0,
+            "saved": false,
+            "stickied": false,
+            "is_self": true,
+            "permalink": format!("/r/rust/comments/{}/", id),
+            "locked": false,
+            "name": format!("t3_{}", id),
+            "created": created_utc,
+            "quarantine": false,
+            "title": "title",
+            "created_utc": created_utc,
+            "visited": false,
+        },
+    })
+}
+
+pub(crate) fn comment(id: &str, parent_id: &str, created_utc: f64, replies: Vec<Value>) -> Value {
+    let mut data = json!({
+        "id": id,
+        "name": format!("t1_{}", id),
+        "subreddit": "rust",
+        "author": "author",
+        "body": "body",
+        "link_id": "t3_abc",
+        "parent_id": parent_id,
+        "created": created_utc,
+        "created_utc": created_utc,
+    });
+    if !replies.is_empty() {
+        data["replies"] = listing(replies, None);
+    }
+
+    json!({ "kind": "t1", "data": data })
+}
+
+pub(crate) fn more(id: &str, parent_id: &str, children: &[&str]) -> Value {
+    json!({
+        "kind": "more",
+        "data": {
+            "count": children.len(),
+            "depth": 0,
+            "name": format!("t1_{}", id),
+            "id": id,
+            "parent_id": parent_id,
+            "children": children,
+        },
+    })
+}
@@ -33,6 +33,74 @@
 //! }
 //! ```
 //!
+//! # Authenticated usage
+//! Subreddits created from a logged in `Me` read through `oauth.reddit.com` with its access
+//! token, which is needed for private subreddits and moderator-only endpoints.
+//!
+//! ```no_run
+//! use roux::Reddit;
+//! # use tokio_test;
+//!
+//! # tokio_test::block_on(async {
+//! let me = Reddit::new("USER_AGENT", "CLIENT_ID", "CLIENT_SECRET")
+//!     .username("USERNAME")
+//!     .password("PASSWORD")
+//!     .login()
+//!     .await
+//!     .unwrap();
+//!
+//! let moderators = me.subreddit("rust").moderators().await;
+//! # })
+//! ```
+//!
+//! # Front page, r/all and r/popular
+//!
+//! ```no_run
+//! use roux::Subreddit;
+//! use roux::util::FeedOption;
+//! # use tokio_test;
+//!
+//! # tokio_test::block_on(async {
+//! let best = Subreddit::front_page().best(25, None).await;
+//! let all = Subreddit::all().controversial(25, None).await;
+//!
+//! // Popular posts in the US.
+//! let options = FeedOption::new().geo_filter("US");
+//! let popular = Subreddit::popular().hot(25, Some(options)).await;
+//! # })
+//! ```
+//!
+//! # Comment options
+//! Open a single comment with two of its parents, oldest replies first:
+//!
+//! ```no_run
+//! use roux::Subreddit;
+//! use roux::util::{CommentOption, CommentSort};
+//! # use tokio_test;
+//!
+//! # tokio_test::block_on(async {
+//! let options = CommentOption::new()
+//!     .comment("t1_def")
+//!     .context(2)
+//!     .sort(CommentSort::Old);
+//! let comments = Subreddit::new("rust")
+//!     .article_comments_with_options("abc", Some(options))
+//!     .await;
+//! # })
+//! ```
+//!
+//! Big threads hide comments behind "more" stubs. `article_comments_full` expands all of
+//! them to get the whole tree:
+//!
+//! ```no_run
+//! use roux::Subreddit;
+//! # use tokio_test;
+//!
+//! # tokio_test::block_on(async {
+//! let comments = Subreddit::new("rust").article_comments_full("abc", None).await;
+//! # })
+//! ```
+//!
 //! # Usage with feed options
 //!
 //! ```rust
@@ -60,45 +128,130 @@
 //! }
 //! ```
 
-extern crate reqwest;
 extern crate serde_json;
 
-use crate::util::{FeedOption, RouxError};
-use reqwest::Client;
+use std::collections::HashSet;
+use std::sync::Arc;
 
+use futures_util::stream::Stream;
+use serde::de::{DeserializeOwned, IgnoredAny};
+
+use crate::me::responses::JsonResponse;
+use crate::me::{Authenticated, Me};
+use crate::responses::BasicListing;
+use crate::stream::{paginate, poll, PollOptions};
+use crate::transport::{self, default_transport, Request, Response, Transport};
+use crate::util::option::{push_comment_options, push_options};
+use crate::util::{
+    url::Endpoints, CommentOption, CommentSort, FeedOption, RateLimiter, RetryPolicy, RouxError,
+};
+
+#[cfg(test)]
+mod fixtures;
+mod more;
+use more::MoreChildren;
 pub mod responses;
+use responses::comments::SubredditReplies;
 use responses::{
-    Moderators, Submissions, SubredditComments, SubredditData, SubredditResponse, SubredditsListing,
+    CommentThing, Gilded, Moderators, Submissions, SubmissionsData, SubredditComments,
+    SubredditCommentsData, SubredditData, SubredditResponse, SubredditsListing,
 };
 
 /// Access subreddits API
-pub struct Subreddits;
+///
+/// ```no_run
+/// use roux::Subreddits;
+/// use roux::util::RetryPolicy;
+/// # use tokio_test;
+///
+/// # tokio_test::block_on(async {
+/// let subreddits = Subreddits::search("rust", Some(10), None).await;
+///
+/// // With a configured client.
+/// let subreddits = Subreddits::new()
+///     .retry_policy(RetryPolicy::never())
+///     .find("rust", Some(10), None)
+///     .await;
+/// # })
+/// ```
+pub struct Subreddits {
+    endpoints: Endpoints,
+    rate_limiter: RateLimiter,
+    retry_policy: RetryPolicy,
+    transport: Arc<dyn Transport>,
+}
 
 impl Subreddits {
+    /// Create a new `Subreddits` instance.
+    pub fn new() -> Subreddits {
+        Subreddits {
+            endpoints: Endpoints::new(),
+            rate_limiter: RateLimiter::new(),
+            retry_policy: RetryPolicy::new(),
+            transport: default_transport(),
+        }
+    }
+
+    /// Sets the endpoints used for requests.
+    pub fn endpoints(mut self, endpoints: Endpoints) -> Subreddits {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Sets the rate limiter, to share one budget between several clients.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Subreddits {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// Sets the retry policy.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Subreddits {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets the transport used to send requests.
+    pub fn transport(mut self, transport: Arc<dyn Transport>) -> Subreddits {
+        self.transport = transport;
+        self
+    }
+
     /// Search subreddits
     pub async fn search(
         name: &str,
         limit: Option<u32>,
         options: Option<FeedOption>,
     ) -> Result<SubredditsListing, RouxError> {
-        let url = &mut format!("https://www.reddit.com/subreddits/search.json?q={}", name);
-
-        if let Some(limit) = limit {
-            url.push_str(&format!("&limit={}", limit));
-        }
+        Subreddits::new().find(name, limit, options).await
+    }
 
-        if let Some(options) = options {
-            options.build_url(url);
-        }
+    /// Search subreddits with the endpoints, rate limiter, retry policy and transport of
+    /// this instance.
+    pub async fn find(
+        &self,
+        name: &str,
+        limit: Option<u32>,
+        options: Option<FeedOption>,
+    ) -> Result<SubredditsListing, RouxError> {
+        let url = &mut format!("{}/subreddits/search.json?q={}", self.endpoints.www, name);
+        push_options(url, limit, options)?;
 
-        let client = Client::new();
+        transport::execute(
+            &*self.transport,
+            &self.rate_limiter,
+            &self.retry_policy,
+            false,
+            &Request::get(url),
+        )
+        .await?
+        .error_for_status()?
+        .json::<SubredditsListing>()
+    }
+}
 
-        Ok(client
-            .get(&url.to_owned())
-            .send()
-            .await?
-            .json::<SubredditsListing>()
-            .await?)
+impl Default for Subreddits {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -107,104 +260,167 @@ pub struct Subreddit {
     /// Name of subreddit.
     pub name: String,
     url: String,
-    client: Client,
+    endpoints: Endpoints,
+    rate_limiter: RateLimiter,
+    retry_policy: RetryPolicy,
+    transport: Arc<dyn Transport>,
+    auth: Option<Arc<Authenticated>>,
 }
 
 impl Subreddit {
     /// Create a new `Subreddit` instance.
     pub fn new(name: &str) -> Subreddit {
-        let subreddit_url = format!("https://www.reddit.com/r/{}", name);
-
-        Subreddit {
+        let mut subreddit = Subreddit {
             name: name.to_owned(),
-            url: subreddit_url,
-            client: Client::new(),
+            url: String::new(),
+            endpoints: Endpoints::new(),
+            rate_limiter: RateLimiter::new(),
+            retry_policy: RetryPolicy::new(),
+            transport: default_transport(),
+            auth: None,
+        };
+
+        subreddit.url = subreddit.base_url();
+        subreddit
+    }
+
+    /// Create a `Subreddit` instance for the front page, which is personalized when
+    /// authenticated. Only the feeds, such as `hot` or `best`, work on it.
+    pub fn front_page() -> Subreddit {
+        Subreddit::new("")
+    }
+
+    /// Create a `Subreddit` instance for `r/all`.
+    pub fn all() -> Subreddit {
+        Subreddit::new("all")
+    }
+
+    /// Create a `Subreddit` instance for `r/popular`, whose feeds can be limited to a region
+    /// with `FeedOption::geo_filter`.
+    pub fn popular() -> Subreddit {
+        Subreddit::new("popular")
+    }
+
+    /// Sets the endpoints used for requests.
+    pub fn endpoints(mut self, endpoints: Endpoints) -> Subreddit {
+        self.endpoints = endpoints;
+        self.url = self.base_url();
+        self
+    }
+
+    /// Read through `oauth.reddit.com` with the access token of `me`. Requests then use the
+    /// endpoints, rate limiter, retry policy and transport of `me` instead of this
+    /// subreddit's own.
+    pub fn authenticated(mut self, me: &Me) -> Subreddit {
+        self.auth = Some(me.auth());
+        self.url = self.base_url();
+        self
+    }
+
+    fn host(&self) -> &str {
+        match self.auth {
+            Some(ref auth) => &auth.config.endpoints.oauth,
+            None => &self.endpoints.www,
+        }
+    }
+
+    fn base_url(&self) -> String {
+        let host = self.host();
+
+        if self.name.is_empty() {
+            host.to_owned()
+        } else {
+            format!("{}/r/{}", host, self.name)
+        }
+    }
+
+    /// Sets the rate limiter, to share one budget between several clients.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Subreddit {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// Sets the retry policy.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Subreddit {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets the transport used to send requests.
+    pub fn transport(mut self, transport: Arc<dyn Transport>) -> Subreddit {
+        self.transport = transport;
+        self
+    }
+
+    async fn get(&self, url: &str) -> Result<Response, RouxError> {
+        if let Some(ref auth) = self.auth {
+            return auth.send(false, Request::get(url)).await;
         }
+
+        transport::execute(
+            &*self.transport,
+            &self.rate_limiter,
+            &self.retry_policy,
+            false,
+            &Request::get(url),
+        )
+        .await?
+        .error_for_status()
     }
 
-    /// Get moderators.
+    /// Get moderators. Reddit only answers this for authenticated subreddits, see
+    /// `Me::subreddit`.
     pub async fn moderators(&self) -> Result<Moderators, RouxError> {
-        // TODO: getting moderators require you to be logged in now
-        Ok(self
-            .client
-            .get(&format!("{}/about/moderators/.json", self.url))
-            .send()
+        self.get(&format!("{}/about/moderators/.json", self.url))
             .await?
             .json::<Moderators>()
-            .await?)
     }
 
     /// Get subreddit data.
     pub async fn about(&self) -> Result<SubredditData, RouxError> {
         Ok(self
-            .client
             .get(&format!("{}/about/.json", self.url))
-            .send()
-            .await?
-            .json::<SubredditResponse>()
             .await?
+            .json::<SubredditResponse>()?
             .data)
     }
 
-    async fn get_feed(
+    async fn get_feed<T: DeserializeOwned>(
         &self,
         ty: &str,
         limit: u32,
         options: Option<FeedOption>,
-    ) -> Result<Submissions, RouxError> {
-        let url = &mut format!("{}/{}.json?limit={}", self.url, ty, limit);
-
-        if let Some(options) = options {
-            options.build_url(url);
-        }
+    ) -> Result<T, RouxError> {
+        let url = &mut format!("{}/{}.json?", self.url, ty);
+        push_options(url, Some(limit), options)?;
 
-        Ok(self
-            .client
-            .get(&url.to_owned())
-            .send()
-            .await?
-            .json::<Submissions>()
-            .await?)
+        self.get(&url.to_owned()).await?.json::<T>()
     }
 
     async fn get_comment_feed(
         &self,
         ty: &str,
-        depth: Option<u32>,
-        limit: Option<u32>,
+        options: Option<CommentOption>,
     ) -> Result<SubredditComments, RouxError> {
         let url = &mut format!("{}/{}.json?", self.url, ty);
-
-        if let Some(depth) = depth {
-            url.push_str(&format!("&depth={}", depth));
-        }
-
-        if let Some(limit) = limit {
-            url.push_str(&format!("&limit={}", limit));
-        }
+        push_comment_options(url, options);
 
         // This is one of the dumbest APIs I've ever seen.
         // The comments for a subreddit are stored in a normal hash map
         // but for posts the comments are in an array with the ONLY item
         // being same hash map as the one for subreddits...
         if url.contains("comments/") {
-            let mut comments = self
-                .client
+            let (_, comments) = self
                 .get(&url.to_owned())
-                .send()
                 .await?
-                .json::<Vec<SubredditComments>>()
-                .await?;
+                .json::<(IgnoredAny, SubredditComments)>()?;
 
-            Ok(comments.pop().unwrap())
+            Ok(comments)
         } else {
             Ok(self
-                .client
                 .get(&url.to_owned())
-                .send()
                 .await?
-                .json::<SubredditComments>()
-                .await?)
+                .json::<SubredditComments>()?)
         }
     }
 
@@ -244,13 +460,134 @@ impl Subreddit {
         self.get_feed("new", limit, options).await
     }
 
+    /// Get controversial posts, from the time period in `options` if given.
+    pub async fn controversial(
+        &self,
+        limit: u32,
+        options: Option<FeedOption>,
+    ) -> Result<Submissions, RouxError> {
+        self.get_feed("controversial", limit, options).await
+    }
+
+    /// Get the best posts. This only works on the front page, other subreddits fail with
+    /// `RouxError::InvalidOption`.
+    pub async fn best(
+        &self,
+        limit: u32,
+        options: Option<FeedOption>,
+    ) -> Result<Submissions, RouxError> {
+        if !self.name.is_empty() {
+            return Err(RouxError::InvalidOption(
+                "best only works on the front page",
+            ));
+        }
+
+        self.get_feed("best", limit, options).await
+    }
+
+    /// Get gilded posts and comments.
+    pub async fn gilded(
+        &self,
+        limit: u32,
+        options: Option<FeedOption>,
+    ) -> Result<Gilded, RouxError> {
+        self.get_feed("gilded", limit, options).await
+    }
+
+    /// Stream hot posts, following `after` from page to page, until `cap` posts have been
+    /// returned or the listing ends.
+    pub fn hot_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Stream<Item = Result<SubmissionsData, RouxError>> + '_ {
+        paginate(options, cap, move |limit, options| {
+            self.get_feed("hot", limit, Some(options))
+        })
+    }
+
+    /// Stream rising posts, following `after` from page to page, until `cap` posts have
+    /// been returned or the listing ends.
+    pub fn rising_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Stream<Item = Result<SubmissionsData, RouxError>> + '_ {
+        paginate(options, cap, move |limit, options| {
+            self.get_feed("rising", limit, Some(options))
+        })
+    }
+
+    /// Stream top posts, following `after` from page to page, until `cap` posts have been
+    /// returned or the listing ends.
+    pub fn top_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Stream<Item = Result<SubmissionsData, RouxError>> + '_ {
+        paginate(options, cap, move |limit, options| {
+            self.get_feed("top", limit, Some(options))
+        })
+    }
+
+    /// Stream latest posts, following `after` from page to page, until `cap` posts have
+    /// been returned or the listing ends. Reddit keeps about the last 1000.
+    pub fn latest_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Stream<Item = Result<SubmissionsData, RouxError>> + '_ {
+        paginate(options, cap, move |limit, options| {
+            self.get_feed("new", limit, Some(options))
+        })
+    }
+
+    /// Stream new posts as they are submitted, polling `new` and returning each post once,
+    /// oldest first. The stream never ends; errors are returned and polling continues.
+    pub fn stream_submissions(
+        &self,
+        options: Option<PollOptions>,
+    ) -> impl Stream<Item = Result<SubmissionsData, RouxError>> + '_ {
+        poll(
+            options,
+            |post: &SubmissionsData| (post.created_utc, Some(&post.name)),
+            move |limit| self.get_feed("new", limit, None),
+        )
+    }
+
+    /// Stream new comments as they are posted, polling the latest comments and returning each
+    /// comment once, oldest first. Create the subreddit with a name like `rust+programming`
+    /// to watch several at once. The stream never ends; errors are returned and polling
+    /// continues.
+    pub fn stream_comments(
+        &self,
+        options: Option<PollOptions>,
+    ) -> impl Stream<Item = Result<SubredditCommentsData, RouxError>> + '_ {
+        poll(
+            options,
+            |comment: &SubredditCommentsData| {
+                let created = comment.created_utc.unwrap_or_default();
+                (created, comment.name.as_deref().or(comment.id.as_deref()))
+            },
+            move |limit| {
+                self.get_feed::<BasicListing<SubredditCommentsData>>("comments", limit, None)
+            },
+        )
+    }
+
     /// Get latest comments.
     pub async fn latest_comments(
         &self,
         depth: Option<u32>,
         limit: Option<u32>,
     ) -> Result<SubredditComments, RouxError> {
-        self.get_comment_feed("comments", depth, limit).await
+        let options = CommentOption {
+            depth,
+            limit,
+            ..CommentOption::default()
+        };
+
+        self.get_comment_feed("comments", Some(options)).await
     }
 
     /// Get comments from article.
@@ -260,17 +597,496 @@ impl Subreddit {
         depth: Option<u32>,
         limit: Option<u32>,
     ) -> Result<SubredditComments, RouxError> {
-        self.get_comment_feed(&format!("comments/{}", article), depth, limit)
+        let options = CommentOption {
+            depth,
+            limit,
+            ..CommentOption::default()
+        };
+
+        self.article_comments_with_options(article, Some(options))
+            .await
+    }
+
+    /// Get comments from article, sorted and focused on a single comment according to
+    /// `options`.
+    pub async fn article_comments_with_options(
+        &self,
+        article: &str,
+        options: Option<CommentOption>,
+    ) -> Result<SubredditComments, RouxError> {
+        self.get_comment_feed(&format!("comments/{}", article), options)
             .await
     }
+
+    /// Get the comments hidden behind "more" stubs of the submission with the fullname
+    /// `link_id`, given the ids in the stubs' `children`. Requests up to 100 ids at a time.
+    /// The comments are returned as a flat list, which can contain new stubs.
+    pub async fn more_children(
+        &self,
+        link_id: &str,
+        children: &[String],
+        sort: Option<CommentSort>,
+    ) -> Result<Vec<CommentThing<SubredditCommentsData>>, RouxError> {
+        let mut things = Vec::new();
+
+        for ids in children.chunks(100) {
+            let url = &mut format!(
+                "{}/api/morechildren.json?api_type=json&link_id={}&children={}",
+                self.host(),
+                link_id,
+                ids.join(",")
+            );
+
+            if let Some(sort) = sort {
+                url.push_str(&format!("&sort={}", sort.as_str()));
+            }
+
+            let response = self.get(url).await?.error_for_api()?;
+            let data = response.json::<JsonResponse<MoreChildren>>()?;
+            things.extend(data.json.data.things);
+        }
+
+        Ok(things)
+    }
+
+    /// Replace every "more" stub in `comments`, the comments of `article`, with the comments
+    /// it hides, until the whole tree has been fetched. "Continue this thread" stubs are
+    /// expanded by loading the parent comment's permalink.
+    pub async fn expand_more(
+        &self,
+        article: &str,
+        comments: &mut SubredditComments,
+        sort: Option<CommentSort>,
+    ) -> Result<(), RouxError> {
+        let link_id = format!("t3_{}", article);
+        let mut expanded = HashSet::new();
+
+        loop {
+            // "Continue this thread" stubs are all named `t1__`, so tell stubs apart by
+            // what they hide.
+            let stubs: Vec<_> = more::take_stubs(comments)
+                .into_iter()
+                .filter(|stub| expanded.insert((stub.parent_id.clone(), stub.children.clone())))
+                .collect();
+
+            if stubs.is_empty() {
+                return Ok(());
+            }
+
+            let ids: Vec<_> = stubs
+                .iter()
+                .flat_map(|stub| stub.children.clone())
+                .collect();
+            let mut things = self.more_children(&link_id, &ids, sort).await?;
+
+            // "Continue this thread" stubs right under the submission have nothing more to
+            // load, as the submission's own page is what was fetched.
+            let threads = stubs
+                .iter()
+                .filter(|stub| stub.children.is_empty() && stub.parent_id.starts_with("t1_"));
+
+            for stub in threads {
+                things.extend(self.continue_thread(article, &stub.parent_id, sort).await?);
+            }
+
+            more::attach(comments, &link_id, things);
+        }
+    }
+
+    /// Get the replies of the comment with the fullname `parent_id` from its permalink.
+    async fn continue_thread(
+        &self,
+        article: &str,
+        parent_id: &str,
+        sort: Option<CommentSort>,
+    ) -> Result<Vec<CommentThing<SubredditCommentsData>>, RouxError> {
+        let mut options = CommentOption::new().comment(parent_id);
+        options.sort = sort;
+
+        let focused = self
+            .article_comments_with_options(article, Some(options))
+            .await?
+            .data
+            .children
+            .into_iter()
+            .find(|child| child.data.name.as_deref() == Some(parent_id));
+
+        let replies = match focused.and_then(|child| child.data.replies) {
+            Some(SubredditReplies::Reply(replies)) => replies.data,
+            _ => return Ok(Vec::new()),
+        };
+
+        Ok(replies
+            .children
+            .into_iter()
+            .map(|reply| CommentThing::Comment(*reply.data))
+            .chain(replies.more.into_iter().map(CommentThing::More))
+            .collect())
+    }
+
+    /// Get the full comment tree of `article`, expanding every "more" stub. This can take
+    /// many requests on big submissions.
+    pub async fn article_comments_full(
+        &self,
+        article: &str,
+        options: Option<CommentOption>,
+    ) -> Result<SubredditComments, RouxError> {
+        let sort = options.as_ref().and_then(|options| options.sort);
+        let mut comments = self.article_comments_with_options(article, options).await?;
+
+        self.expand_more(article, &mut comments, sort).await?;
+        Ok(comments)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use futures_util::{StreamExt, TryStreamExt};
+
+    use serde_json::json;
+
+    use super::fixtures::{comment, listing, more, submission};
+    use super::responses::comments::SubredditReplies;
+    use super::responses::{GildedData, SubredditCommentsData};
     use super::Subreddit;
     use super::Subreddits;
+    use crate::stream::PollOptions;
+    use crate::transport::{Method, MockTransport, Response};
+    use crate::util::url::Endpoints;
+    use crate::util::{CommentOption, CommentSort, FeedOption, RetryPolicy, RouxError, TimePeriod};
     use tokio;
 
+    #[tokio::test]
+    async fn test_paginated_with_mock() {
+        let mock = Arc::new(MockTransport::new());
+        mock.route(
+            Method::Get,
+            "/r/rust/new.json?limit=100",
+            Response::new(
+                200,
+                &listing(
+                    vec![submission("a", 3.0), submission("b", 2.0)],
+                    Some("t3_b"),
+                )
+                .to_string(),
+            ),
+        );
+        mock.route(
+            Method::Get,
+            "/r/rust/new.json?limit=100&after=t3_b&count=2",
+            Response::new(200, &listing(vec![submission("c", 1.0)], None).to_string()),
+        );
+
+        let subreddit = Subreddit::new("rust").transport(mock.clone());
+        let posts: Vec<_> = subreddit
+            .latest_paginated(None, None)
+            .try_collect()
+            .await
+            .unwrap();
+
+        let ids: Vec<_> = posts.iter().map(|post| &post.id[..]).collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_submissions_with_mock() {
+        let mock = Arc::new(MockTransport::new());
+        mock.route(
+            Method::Get,
+            "/r/rust/new.json?limit=100",
+            Response::new(
+                200,
+                &listing(vec![submission("b", 2.0), submission("a", 1.0)], None).to_string(),
+            ),
+        );
+        mock.route(
+            Method::Get,
+            "/r/rust/new.json?limit=100",
+            Response::new(
+                200,
+                &listing(vec![submission("c", 3.0), submission("b", 2.0)], None).to_string(),
+            ),
+        );
+
+        let subreddit = Subreddit::new("rust").transport(mock.clone());
+        let options = PollOptions::new().min_interval(Duration::from_millis(1));
+        let posts: Vec<_> = subreddit
+            .stream_submissions(Some(options))
+            .take(3)
+            .try_collect()
+            .await
+            .unwrap();
+
+        let ids: Vec<_> = posts.iter().map(|post| &post.id[..]).collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_comments_with_mock() {
+        let mock = Arc::new(MockTransport::new());
+        mock.route(
+            Method::Get,
+            "/r/rust+programming/comments.json?limit=100",
+            Response::new(
+                200,
+                &listing(
+                    vec![
+                        comment("b", "t3_abc", 2.0, vec![]),
+                        comment("a", "t3_abc", 1.0, vec![]),
+                    ],
+                    None,
+                )
+                .to_string(),
+            ),
+        );
+        mock.route(
+            Method::Get,
+            "/r/rust+programming/comments.json?limit=100",
+            Response::new(
+                200,
+                &listing(
+                    vec![
+                        comment("c", "t3_abc", 3.0, vec![]),
+                        comment("b", "t3_abc", 2.0, vec![]),
+                    ],
+                    None,
+                )
+                .to_string(),
+            ),
+        );
+
+        let subreddit = Subreddit::new("rust+programming").transport(mock.clone());
+        let options = PollOptions::new().min_interval(Duration::from_millis(1));
+        let comments: Vec<_> = subreddit
+            .stream_comments(Some(options))
+            .take(3)
+            .try_collect()
+            .await
+            .unwrap();
+
+        let ids: Vec<_> = comments
+            .iter()
+            .map(|comment| comment.id.as_deref().unwrap())
+            .collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_sorts_with_mock() {
+        let mock = Arc::new(MockTransport::new());
+        let empty = listing(vec![], None).to_string();
+        for url in [
+            "https://www.reddit.com/r/rust/controversial.json?limit=10&t=week",
+            "https://www.reddit.com/best.json?limit=10",
+            "https://www.reddit.com/r/popular/hot.json?limit=10&g=US",
+        ] {
+            mock.route(Method::Get, url, Response::new(200, &empty));
+        }
+
+        let week = FeedOption::new().period(TimePeriod::ThisWeek);
+        let us = FeedOption::new().geo_filter("US");
+
+        let rust = Subreddit::new("rust").transport(mock.clone());
+        assert!(rust.controversial(10, Some(week)).await.is_ok());
+
+        let front_page = Subreddit::front_page().transport(mock.clone());
+        assert!(front_page.best(10, None).await.is_ok());
+
+        let popular = Subreddit::popular().transport(mock.clone());
+        assert!(popular.hot(10, Some(us)).await.is_ok());
+
+        assert!(matches!(
+            rust.best(10, None).await,
+            Err(RouxError::InvalidOption(_))
+        ));
+
+        assert_eq!(mock.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_find_with_mock() {
+        let mock = Arc::new(MockTransport::new());
+        mock.route(
+            Method::Get,
+            "http://localhost/subreddits/search.json?q=rust&limit=3",
+            Response::new(200, &listing(vec![], None).to_string()),
+        );
+
+        let subreddits = Subreddits::new()
+            .endpoints(Endpoints::new().www("http://localhost"))
+            .transport(mock.clone())
+            .find("rust", Some(3), None)
+            .await
+            .unwrap();
+
+        assert!(subreddits.data.children.is_empty());
+        assert_eq!(mock.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gilded_with_mock() {
+        let mock = Arc::new(MockTransport::new());
+        mock.route(
+            Method::Get,
+            "https://www.reddit.com/r/all/gilded.json?limit=10",
+            Response::new(
+                200,
+                &listing(
+                    vec![submission("a", 2.0), comment("b", "t3_abc", 1.0, vec![])],
+                    None,
+                )
+                .to_string(),
+            ),
+        );
+
+        let all = Subreddit::all().transport(mock.clone());
+        let gilded = all.gilded(10, None).await.unwrap();
+
+        match &gilded.data.children[..] {
+            [GildedData::Submission(post), GildedData::Comment(comment)] => {
+                assert_eq!(post.id, "a");
+                assert_eq!(comment.id.as_deref(), Some("b"));
+            }
+            children => panic!("expected a submission and a comment, got {:?}", children),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_article_comments_with_mock() {
+        let mock = Arc::new(MockTransport::new());
+        mock.route(
+            Method::Get,
+            "/r/rust/comments/abc.json?sort=old&comment=def&context=2",
+            Response::new(
+                200,
+                &format!(
+                    "[{}, {}]",
+                    listing(vec![], None),
+                    listing(vec![comment("def", "t3_abc", 1.0, vec![])], None)
+                ),
+            ),
+        );
+
+        let subreddit = Subreddit::new("rust").transport(mock.clone());
+        let options = CommentOption::new()
+            .sort(CommentSort::Old)
+            .comment("t1_def")
+            .context(2);
+        let comments = subreddit
+            .article_comments_with_options("abc", Some(options))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            comments.data.children[0].data.name,
+            Some("t1_def".to_owned())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expand_more_with_mock() {
+        let a = comment("a", "t3_abc", 1.0, vec![more("_", "t1_a", &[])]);
+        let top = listing(
+            vec![
+                a,
+                more("b", "t3_abc", &["b", "c"]),
+                more("_", "t3_abc", &[]),
+            ],
+            None,
+        );
+        let focused = comment("a", "t3_abc", 1.0, vec![comment("d", "t1_a", 1.0, vec![])]);
+
+        let mock = Arc::new(MockTransport::new());
+        mock.route(
+            Method::Get,
+            "/r/rust/comments/abc.json?",
+            Response::new(200, &format!("[{}, {}]", listing(vec![], None), top)),
+        );
+        mock.route(
+            Method::Get,
+            "/api/morechildren.json?api_type=json&link_id=t3_abc&children=b,c",
+            Response::new(
+                200,
+                &json!({"json": {"errors": [], "data": {"things": [
+                    comment("b", "t3_abc", 1.0, vec![]),
+                    comment("c", "t1_b", 1.0, vec![]),
+                    more("e", "t1_c", &["e"]),
+                ]}}})
+                .to_string(),
+            ),
+        );
+        mock.route(
+            Method::Get,
+            "/api/morechildren.json?api_type=json&link_id=t3_abc&children=e",
+            Response::new(
+                200,
+                &json!({"json": {"errors": [], "data": {"things": [
+                    comment("e", "t1_c", 1.0, vec![]),
+                ]}}})
+                .to_string(),
+            ),
+        );
+        mock.route(
+            Method::Get,
+            "/r/rust/comments/abc.json?comment=a",
+            Response::new(
+                200,
+                &format!(
+                    "[{}, {}]",
+                    listing(vec![], None),
+                    listing(vec![focused], None)
+                ),
+            ),
+        );
+
+        let subreddit = Subreddit::new("rust").transport(mock.clone());
+        let comments = subreddit.article_comments_full("abc", None).await.unwrap();
+
+        let replies = |data: &SubredditCommentsData| match data.replies {
+            Some(SubredditReplies::Reply(ref replies)) => replies
+                .data
+                .children
+                .iter()
+                .map(|reply| reply.data.id.clone().unwrap())
+                .collect::<Vec<_>>(),
+            _ => Vec::new(),
+        };
+
+        let top: Vec<_> = comments.data.children.iter().map(|c| &c.data).collect();
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].id.as_deref(), Some("a"));
+        assert_eq!(replies(top[0]), ["d"]);
+        assert_eq!(top[1].id.as_deref(), Some("b"));
+        assert_eq!(replies(top[1]), ["c"]);
+        assert!(comments.data.more.is_empty());
+        assert_eq!(mock.requests().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_mock() {
+        let mock = Arc::new(MockTransport::new());
+        mock.route(Method::Get, "/r/rust/hot", Response::new(503, ""));
+        mock.route(
+            Method::Get,
+            "/r/rust/hot",
+            Response::new(
+                200,
+                r#"{"kind": "Listing", "data": {"after": "t3_abc", "children": []}}"#,
+            ),
+        );
+
+        let subreddit = Subreddit::new("rust")
+            .transport(mock.clone())
+            .retry_policy(RetryPolicy::new().base_delay(Duration::from_millis(1)));
+
+        let hot = subreddit.hot(25, None).await.unwrap();
+        assert_eq!(hot.data.after, Some("t3_abc".to_owned()));
+        assert_eq!(mock.requests().len(), 2);
+    }
+
     #[tokio::test]
     async fn test_no_auth() {
         let subreddit = Subreddit::new("astolfo");
@@ -0,0 +1,112 @@
+//! Splices the comments hidden behind "more" stubs back into a comment tree.
+use std::borrow::BorrowMut;
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::responses::BasicThing;
+use crate::subreddit::responses::comments::{CommentListing, SubredditReplies};
+use crate::subreddit::responses::{CommentThing, More, SubredditComments, SubredditCommentsData};
+
+/// The data of a `/api/morechildren` response.
+#[derive(Debug, Deserialize)]
+pub(crate) struct MoreChildren {
+    pub things: Vec<CommentThing<SubredditCommentsData>>,
+}
+
+/// Removes every "more" stub from `comments`, returning them.
+pub(crate) fn take_stubs(comments: &mut SubredditComments) -> Vec<More> {
+    let mut stubs = Vec::new();
+    take_from(&mut comments.data, &mut stubs);
+    stubs
+}
+
+fn take_from<T: BorrowMut<SubredditCommentsData>>(
+    listing: &mut CommentListing<T>,
+    stubs: &mut Vec<More>,
+) {
+    stubs.append(&mut listing.more);
+
+    for child in listing.children.iter_mut() {
+        if let Some(SubredditReplies::Reply(ref mut replies)) = child.data.borrow_mut().replies {
+            take_from(&mut replies.data, stubs);
+        }
+    }
+}
+
+/// Adds `things` to `comments` under their parents. Things whose parent can't be found are
+/// added at the top level so nothing is lost.
+pub(crate) fn attach(
+    comments: &mut SubredditComments,
+    link_id: &str,
+    things: Vec<CommentThing<SubredditCommentsData>>,
+) {
+    let mut orphans: HashMap<String, Vec<CommentThing<SubredditCommentsData>>> = HashMap::new();
+    for thing in things {
+        let parent_id = match thing {
+            CommentThing::Comment(ref comment) => comment.parent_id.clone().unwrap_or_default(),
+            CommentThing::More(ref more) => more.parent_id.clone(),
+        };
+        orphans.entry(parent_id).or_default().push(thing);
+    }
+
+    let listing = &mut comments.data;
+    push_things(listing, orphans.remove(link_id).unwrap_or_default());
+    attach_to(&mut listing.children, &mut orphans);
+
+    for (_, things) in orphans {
+        push_things(listing, things);
+    }
+}
+
+fn attach_to<T: BorrowMut<SubredditCommentsData>>(
+    children: &mut [BasicThing<T>],
+    orphans: &mut HashMap<String, Vec<CommentThing<SubredditCommentsData>>>,
+) {
+    for child in children.iter_mut() {
+        let data = child.data.borrow_mut();
+
+        if let Some(things) = data.name.as_ref().and_then(|name| orphans.remove(name)) {
+            if !matches!(data.replies, Some(SubredditReplies::Reply(_))) {
+                data.replies = Some(SubredditReplies::Reply(empty_listing()));
+            }
+
+            if let Some(SubredditReplies::Reply(ref mut replies)) = data.replies {
+                push_things(&mut replies.data, things);
+            }
+        }
+
+        if let Some(SubredditReplies::Reply(ref mut replies)) = data.replies {
+            attach_to(&mut replies.data.children, orphans);
+        }
+    }
+}
+
+fn push_things<T: From<SubredditCommentsData>>(
+    listing: &mut CommentListing<T>,
+    things: Vec<CommentThing<SubredditCommentsData>>,
+) {
+    for thing in things {
+        match thing {
+            CommentThing::Comment(comment) => listing.children.push(BasicThing {
+                kind: "t1".to_owned(),
+                data: T::from(comment),
+            }),
+            CommentThing::More(more) => listing.more.push(more),
+        }
+    }
+}
+
+fn empty_listing<T>() -> BasicThing<CommentListing<T>> {
+    BasicThing {
+        kind: "Listing".to_owned(),
+        data: CommentListing {
+            modhash: None,
+            dist: None,
+            after: None,
+            before: None,
+            children: Vec::new(),
+            more: Vec::new(),
+        },
+    }
+}
@@ -1,6 +1,6 @@
 //! # Subreddit Comment Responses
-use crate::responses::BasicListing;
-use serde::Deserialize;
+use crate::responses::{BasicThing, Listing};
+use serde::{Deserialize, Deserializer};
 
 /// SubredditCommentsData
 /// Everything is an option to deal with both `latest_comments` and `article_comments`
@@ -104,10 +104,87 @@ pub struct SubredditCommentsData {
     pub ups: Option<i32>,
     /// Replies
     pub replies: Option<SubredditReplies>,
+    /// How deep in the thread this is, 0 for top-level comments
+    pub depth: Option<i32>,
+}
+
+/// A "load more comments" stub, standing in for comments that weren't returned. Expand
+/// stubs with `Subreddit::expand_more`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct More {
+    /// Id of the stub
+    pub id: String,
+    /// Fullname of the stub, `t1__` for "continue this thread" stubs
+    pub name: String,
+    /// Fullname of the comment or submission the hidden comments reply to
+    pub parent_id: String,
+    /// Number of hidden comments
+    pub count: i32,
+    /// How deep in the thread the hidden comments are
+    pub depth: i32,
+    /// Ids of the hidden comments. Empty for "continue this thread" stubs, whose comments
+    /// are only shown on the parent's permalink.
+    pub children: Vec<String>,
+}
+
+/// A comment or a "more" stub, as listed by `Subreddit::more_children`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", content = "data")]
+pub enum CommentThing<T> {
+    /// A comment
+    #[serde(rename = "t1")]
+    Comment(T),
+    /// A "more" stub
+    #[serde(rename = "more")]
+    More(More),
+}
+
+/// A listing of comments, with the "more" stubs among them kept apart.
+#[derive(Debug)]
+pub struct CommentListing<T> {
+    /// Modhash
+    pub modhash: Option<String>,
+    /// I dunno what this is
+    pub dist: Option<i32>,
+    /// The fullname of the listing that follows after this page.
+    pub after: Option<String>,
+    /// The fullname of the listing that follows before this page.
+    pub before: Option<String>,
+    /// The comments.
+    pub children: Vec<BasicThing<T>>,
+    /// The "more" stubs.
+    pub more: Vec<More>,
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for CommentListing<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let listing = Listing::<CommentThing<T>>::deserialize(deserializer)?;
+        let mut children = Vec::new();
+        let mut more = Vec::new();
+
+        for child in listing.children {
+            match child {
+                CommentThing::Comment(data) => children.push(BasicThing {
+                    kind: "t1".to_owned(),
+                    data,
+                }),
+                CommentThing::More(stub) => more.push(stub),
+            }
+        }
+
+        Ok(CommentListing {
+            modhash: listing.modhash,
+            dist: listing.dist,
+            after: listing.after,
+            before: listing.before,
+            children,
+            more,
+        })
+    }
 }
 
 /// Reply
-pub type Reply = BasicListing<Box<SubredditCommentsData>>;
+pub type Reply = BasicThing<CommentListing<Box<SubredditCommentsData>>>;
 
 /// Replies can be more comments or an empty string
 #[derive(Debug, Deserialize)]
@@ -120,4 +197,4 @@ pub enum SubredditReplies {
 }
 
 /// SubredditComments
-pub type SubredditComments = BasicListing<SubredditCommentsData>;
+pub type SubredditComments = BasicThing<CommentListing<SubredditCommentsData>>;
@@ -0,0 +1,19 @@
+//! # Subreddit Gilded Responses
+use crate::responses::{BasicThing, Listing};
+use crate::subreddit::responses::{SubmissionsData, SubredditCommentsData};
+use serde::Deserialize;
+
+/// A gilded submission or comment.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", content = "data")]
+pub enum GildedData {
+    /// Submission
+    #[serde(rename = "t3")]
+    Submission(SubmissionsData),
+    /// Comment
+    #[serde(rename = "t1")]
+    Comment(SubredditCommentsData),
+}
+
+/// Gilded
+pub type Gilded = BasicThing<Listing<GildedData>>;
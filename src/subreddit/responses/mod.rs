@@ -9,4 +9,10 @@ pub mod subreddits;
 pub use subreddits::{SubredditData, SubredditResponse, SubredditsListing};
 
 pub mod comments;
-pub use comments::{SubredditComments, SubredditCommentsData};
+pub use comments::{CommentThing, More, SubredditComments, SubredditCommentsData};
+
+pub mod gilded;
+pub use gilded::{Gilded, GildedData};
+
+pub mod tree;
+pub use tree::{Bfs, CommentRef, CommentTree, Dfs};
@@ -27,9 +27,9 @@ pub enum AccountsActive {
 #[derive(Debug, Deserialize)]
 pub struct RichtextFlair {
     /// contains the string text
-    e: String,
+    pub e: String,
     /// contains the literal string that comprises the user's flair.
-    t: String,
+    pub t: String,
 }
 
 /// SubredditData
@@ -0,0 +1,370 @@
+//! # Comment Tree
+//! A typed tree of the comments of a submission, built from `Subreddit::article_comments`.
+use std::collections::{HashMap, VecDeque};
+
+use crate::subreddit::responses::comments::{CommentListing, SubredditComments, SubredditReplies};
+use crate::subreddit::responses::{More, SubredditCommentsData};
+
+/// The comments of a submission, with links between parents and replies.
+///
+/// "More" stubs are kept apart from the comments, see `CommentTree::more` and
+/// `CommentRef::more`. Expand them first with `Subreddit::article_comments_full` to get every
+/// comment.
+///
+/// ```no_run
+/// use roux::Subreddit;
+/// use roux::subreddit::responses::CommentTree;
+/// # use tokio_test;
+///
+/// # tokio_test::block_on(async {
+/// let comments = Subreddit::new("rust").article_comments("abc", None, None).await;
+/// let tree = CommentTree::from(comments.unwrap());
+///
+/// for comment in tree.dfs() {
+///     let indent = "  ".repeat(comment.depth());
+///     println!("{}{:?}", indent, comment.data().body);
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct CommentTree {
+    nodes: Vec<Node>,
+    roots: Vec<usize>,
+    more: Vec<More>,
+    ids: HashMap<String, usize>,
+}
+
+#[derive(Debug)]
+struct Node {
+    data: SubredditCommentsData,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    more: Vec<More>,
+    depth: usize,
+}
+
+impl CommentTree {
+    /// Build a tree from the comments of a submission.
+    pub fn new(comments: SubredditComments) -> CommentTree {
+        let mut tree = CommentTree {
+            nodes: Vec::new(),
+            roots: Vec::new(),
+            more: Vec::new(),
+            ids: HashMap::new(),
+        };
+
+        let (roots, more) = tree.add(comments.data, None, 0);
+        tree.roots = roots;
+        tree.more = more;
+        tree
+    }
+
+    fn add<T: Into<Box<SubredditCommentsData>>>(
+        &mut self,
+        listing: CommentListing<T>,
+        parent: Option<usize>,
+        depth: usize,
+    ) -> (Vec<usize>, Vec<More>) {
+        let mut indices = Vec::new();
+
+        for thing in listing.children {
+            let mut data = *thing.data.into();
+            let index = self.nodes.len();
+            let replies = data.replies.take();
+
+            if let Some(ref id) = data.id {
+                self.ids.insert(id.clone(), index);
+            }
+
+            self.nodes.push(Node {
+                data,
+                parent,
+                children: Vec::new(),
+                more: Vec::new(),
+                depth,
+            });
+
+            if let Some(SubredditReplies::Reply(replies)) = replies {
+                let (children, more) = self.add(replies.data, Some(index), depth + 1);
+                self.nodes[index].children = children;
+                self.nodes[index].more = more;
+            }
+
+            indices.push(index);
+        }
+
+        (indices, listing.more)
+    }
+
+    fn node(&self, index: usize) -> CommentRef<'_> {
+        CommentRef { tree: self, index }
+    }
+
+    /// Get the number of comments.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether there are no comments.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Get the number of levels of replies, 0 for no comments and 1 for only top-level
+    /// comments.
+    pub fn depth(&self) -> usize {
+        self.nodes
+            .iter()
+            .map(|node| node.depth + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Get the top-level comments.
+    pub fn roots(&self) -> impl Iterator<Item = CommentRef<'_>> {
+        self.roots.iter().map(move |&index| self.node(index))
+    }
+
+    /// Get the "more" stubs among the top-level comments.
+    pub fn more(&self) -> &[More] {
+        &self.more
+    }
+
+    /// Whether some comments are hidden behind "more" stubs, anywhere in the tree.
+    pub fn is_truncated(&self) -> bool {
+        !self.more.is_empty() || self.nodes.iter().any(|node| !node.more.is_empty())
+    }
+
+    /// Get the comment with the id `id`, with or without the `t1_` prefix.
+    pub fn get(&self, id: &str) -> Option<CommentRef<'_>> {
+        let id = id.trim_start_matches("t1_");
+        self.ids.get(id).map(|&index| self.node(index))
+    }
+
+    /// Iterate over every comment depth-first, each followed by its replies.
+    pub fn dfs(&self) -> Dfs<'_> {
+        Dfs {
+            tree: self,
+            stack: self.roots.iter().rev().copied().collect(),
+        }
+    }
+
+    /// Iterate over every comment breadth-first, level by level.
+    pub fn bfs(&self) -> Bfs<'_> {
+        Bfs {
+            tree: self,
+            queue: self.roots.iter().copied().collect(),
+        }
+    }
+
+    /// Turn the tree into a list of its comments in depth-first order, leaving out the
+    /// "more" stubs.
+    pub fn flatten(self) -> Vec<SubredditCommentsData> {
+        let order: Vec<_> = self.dfs().map(|comment| comment.index).collect();
+        let mut nodes: Vec<_> = self.nodes.into_iter().map(Some).collect();
+
+        order
+            .into_iter()
+            .filter_map(|index| nodes[index].take())
+            .map(|node| node.data)
+            .collect()
+    }
+}
+
+impl From<SubredditComments> for CommentTree {
+    fn from(comments: SubredditComments) -> Self {
+        CommentTree::new(comments)
+    }
+}
+
+/// A comment in a `CommentTree`.
+#[derive(Copy, Clone, Debug)]
+pub struct CommentRef<'a> {
+    tree: &'a CommentTree,
+    index: usize,
+}
+
+impl<'a> CommentRef<'a> {
+    /// Get the comment. Its `replies` are `None`, use `children` instead.
+    pub fn data(&self) -> &'a SubredditCommentsData {
+        &self.tree.nodes[self.index].data
+    }
+
+    /// Get the comment this replies to, `None` for top-level comments.
+    pub fn parent(&self) -> Option<CommentRef<'a>> {
+        let tree = self.tree;
+        tree.nodes[self.index]
+            .parent
+            .map(|index| CommentRef { tree, index })
+    }
+
+    /// Get the direct replies.
+    pub fn children(&self) -> impl Iterator<Item = CommentRef<'a>> {
+        let tree = self.tree;
+        tree.nodes[self.index]
+            .children
+            .iter()
+            .map(move |&index| CommentRef { tree, index })
+    }
+
+    /// Get the "more" stubs among the direct replies, standing in for replies that weren't
+    /// loaded.
+    pub fn more(&self) -> &'a [More] {
+        &self.tree.nodes[self.index].more
+    }
+
+    /// Get how deep the comment is, 0 for top-level comments.
+    pub fn depth(&self) -> usize {
+        self.tree.nodes[self.index].depth
+    }
+
+    /// Iterate over every reply below this comment depth-first.
+    pub fn descendants(&self) -> Dfs<'a> {
+        Dfs {
+            tree: self.tree,
+            stack: self.tree.nodes[self.index]
+                .children
+                .iter()
+                .rev()
+                .copied()
+                .collect(),
+        }
+    }
+}
+
+/// Depth-first iterator over a `CommentTree`.
+#[derive(Debug)]
+pub struct Dfs<'a> {
+    tree: &'a CommentTree,
+    stack: Vec<usize>,
+}
+
+impl<'a> Iterator for Dfs<'a> {
+    type Item = CommentRef<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.stack.pop()?;
+        let children = &self.tree.nodes[index].children;
+        self.stack.extend(children.iter().rev());
+
+        Some(CommentRef {
+            tree: self.tree,
+            index,
+        })
+    }
+}
+
+/// Breadth-first iterator over a `CommentTree`.
+#[derive(Debug)]
+pub struct Bfs<'a> {
+    tree: &'a CommentTree,
+    queue: VecDeque<usize>,
+}
+
+impl<'a> Iterator for Bfs<'a> {
+    type Item = CommentRef<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.queue.pop_front()?;
+        self.queue.extend(&self.tree.nodes[index].children);
+
+        Some(CommentRef {
+            tree: self.tree,
+            index,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CommentRef, CommentTree};
+    use crate::subreddit::fixtures::{comment, listing, more};
+    use crate::subreddit::responses::SubredditComments;
+
+    fn tree() -> CommentTree {
+        // a
+        //   b
+        //     d
+        //   c
+        // e
+        let a = comment(
+            "a",
+            "t3_abc",
+            1.0,
+            vec![
+                comment("b", "t1_a", 1.0, vec![comment("d", "t1_b", 1.0, vec![])]),
+                comment("c", "t1_a", 1.0, vec![more("g", "t1_c", &["g", "h"])]),
+            ],
+        );
+        let comments = listing(
+            vec![
+                a,
+                comment("e", "t3_abc", 1.0, vec![]),
+                more("f", "t3_abc", &["f"]),
+            ],
+            None,
+        );
+
+        CommentTree::from(serde_json::from_value::<SubredditComments>(comments).unwrap())
+    }
+
+    fn ids<'a>(comments: impl Iterator<Item = CommentRef<'a>>) -> Vec<String> {
+        comments
+            .map(|comment| comment.data().id.clone().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_traversal() {
+        let tree = tree();
+
+        assert_eq!(tree.len(), 5);
+        assert_eq!(tree.depth(), 3);
+        assert_eq!(ids(tree.roots()), ["a", "e"]);
+        assert_eq!(ids(tree.dfs()), ["a", "b", "d", "c", "e"]);
+        assert_eq!(ids(tree.bfs()), ["a", "e", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn test_links() {
+        let tree = tree();
+        let d = tree.get("t1_d").unwrap();
+
+        assert_eq!(d.depth(), 2);
+        assert_eq!(d.parent().unwrap().data().id.as_deref(), Some("b"));
+        assert!(d.data().replies.is_none());
+
+        let a = tree.get("a").unwrap();
+        assert!(a.parent().is_none());
+        assert_eq!(ids(a.children()), ["b", "c"]);
+        assert_eq!(ids(a.descendants()), ["b", "d", "c"]);
+        assert!(tree.get("f").is_none());
+    }
+
+    #[test]
+    fn test_more() {
+        let tree = tree();
+        assert!(tree.is_truncated());
+
+        let more = tree.more();
+        assert_eq!(more.len(), 1);
+        assert_eq!(more[0].children, ["f"]);
+
+        let c = tree.get("c").unwrap();
+        assert_eq!(c.more()[0].count, 2);
+        assert!(tree.get("a").unwrap().more().is_empty());
+
+        let comments = listing(vec![comment("a", "t3_abc", 1.0, vec![])], None);
+        let tree =
+            CommentTree::from(serde_json::from_value::<SubredditComments>(comments).unwrap());
+        assert!(!tree.is_truncated());
+    }
+
+    #[test]
+    fn test_flatten() {
+        let comments = tree().flatten();
+        let ids: Vec<_> = comments.iter().map(|c| c.id.as_deref().unwrap()).collect();
+
+        assert_eq!(ids, ["a", "b", "d", "c", "e"]);
+    }
+}
@@ -0,0 +1,149 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::Transport;
+use crate::util::RouxError;
+
+/// TLS implementation used by a client built with `ClientBuilder`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Tls {
+    /// Whichever backend reqwest picks by default.
+    Default,
+    /// The platform's native TLS. Needs the `native-tls` feature.
+    #[cfg(feature = "native-tls")]
+    NativeTls,
+    /// Rustls. Needs the `rustls` feature.
+    #[cfg(feature = "rustls")]
+    Rustls,
+}
+
+/// Builds a reqwest backed transport with custom timeouts, proxy and connection settings.
+///
+/// The resulting transport is one connection pool that can be shared by every handle:
+/// ```
+/// use std::time::Duration;
+/// use roux::{Reddit, Subreddit, User};
+/// use roux::transport::ClientBuilder;
+///
+/// let client = ClientBuilder::new()
+///     .timeout(Duration::from_secs(10))
+///     .connect_timeout(Duration::from_secs(3))
+///     .pool_max_idle_per_host(4)
+///     .build()
+///     .unwrap();
+///
+/// let reddit = Reddit::new("USER_AGENT", "CLIENT_ID", "CLIENT_SECRET").transport(client.clone());
+/// let subreddit = Subreddit::new("rust").transport(client.clone());
+/// let user = User::new("beanpup_py").transport(client);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ClientBuilder {
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) connect_timeout: Option<Duration>,
+    pub(crate) proxy: Option<String>,
+    pub(crate) tls: Tls,
+    pub(crate) pool_max_idle_per_host: Option<usize>,
+    pub(crate) pool_idle_timeout: Option<Duration>,
+}
+
+impl ClientBuilder {
+    /// Create a new `ClientBuilder` instance with reqwest's defaults.
+    pub fn new() -> ClientBuilder {
+        ClientBuilder {
+            timeout: None,
+            connect_timeout: None,
+            proxy: None,
+            tls: Tls::Default,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+        }
+    }
+
+    /// Set the timeout for a whole request, from connecting until the body has been read.
+    pub fn timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timeout for connecting to Reddit.
+    pub fn connect_timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Send every request through the proxy at `url`, e.g. `http://localhost:8080`.
+    pub fn proxy(mut self, url: &str) -> ClientBuilder {
+        self.proxy = Some(url.to_owned());
+        self
+    }
+
+    /// Set the TLS backend.
+    pub fn tls(mut self, tls: Tls) -> ClientBuilder {
+        self.tls = tls;
+        self
+    }
+
+    /// Set the maximum number of idle connections kept open per host.
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> ClientBuilder {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Set how long idle connections are kept open.
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Build the transport, using reqwest 0.11 or 0.10 depending on the enabled features.
+    pub fn build(&self) -> Result<Arc<dyn Transport>, RouxError> {
+        #[cfg(feature = "tokio_new")]
+        let transport = super::ReqwestTransport::from_builder(self)?;
+        #[cfg(all(feature = "tokio_back_compat", not(feature = "tokio_new")))]
+        let transport = super::ReqwestBackCompatTransport::from_builder(self)?;
+
+        Ok(Arc::new(transport))
+    }
+
+    /// Build a transport for `roux::blocking`.
+    #[cfg(feature = "blocking")]
+    pub fn build_blocking(&self) -> Result<Arc<dyn Transport>, RouxError> {
+        Ok(Arc::new(super::ReqwestBlockingTransport::from_builder(
+            self,
+        )?))
+    }
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::ClientBuilder;
+    use crate::util::RouxError;
+
+    #[test]
+    fn test_build() {
+        let builder = ClientBuilder::new()
+            .timeout(Duration::from_secs(10))
+            .connect_timeout(Duration::from_secs(3))
+            .proxy("http://localhost:8080")
+            .pool_max_idle_per_host(2)
+            .pool_idle_timeout(Duration::from_secs(30));
+
+        assert!(builder.build().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_proxy() {
+        match ClientBuilder::new().proxy("not a proxy").build() {
+            Err(RouxError::Network(_)) => {}
+            _ => panic!("expected an invalid proxy to fail"),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::sync::Mutex;
+
+use super::{BoxFuture, Method, Request, Response, Transport};
+use crate::util::RouxError;
+
+/// An in-memory transport that answers requests with canned responses and records every
+/// request it receives.
+///
+/// Each route answers one request whose url contains the route's url. Routes are tried in
+/// the order they were added, so add a route several times to answer repeated requests.
+///
+/// ```
+/// use std::sync::Arc;
+/// use roux::Subreddit;
+/// use roux::transport::{Method, MockTransport, Response};
+/// # use tokio_test;
+///
+/// # tokio_test::block_on(async {
+/// let mock = Arc::new(MockTransport::new());
+/// mock.route(Method::Get, "/r/rust/about", Response::new(404, ""));
+///
+/// let subreddit = Subreddit::new("rust").transport(mock.clone());
+/// assert!(subreddit.about().await.is_err());
+/// assert_eq!(mock.requests().len(), 1);
+/// # })
+/// ```
+#[derive(Debug, Default)]
+pub struct MockTransport {
+    routes: Mutex<Vec<(Method, String, Response)>>,
+    requests: Mutex<Vec<Request>>,
+}
+
+impl MockTransport {
+    /// Create a new `MockTransport` instance.
+    pub fn new() -> MockTransport {
+        MockTransport::default()
+    }
+
+    /// Answer the next `method` request whose url contains `url` with `response`.
+    pub fn route(&self, method: Method, url: &str, response: Response) {
+        self.routes
+            .lock()
+            .unwrap()
+            .push((method, url.to_owned(), response));
+    }
+
+    /// Every request received so far.
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Transport for MockTransport {
+    fn send(&self, request: Request) -> BoxFuture<'_, Result<Response, RouxError>> {
+        let response = {
+            let mut routes = self.routes.lock().unwrap();
+
+            routes
+                .iter()
+                .position(|(method, url, _)| *method == request.method && request.url.contains(url))
+                .map(|i| routes.remove(i).2)
+        };
+
+        let result = response.ok_or_else(|| {
+            RouxError::Network(
+                format!("no route for {} {}", request.method.as_str(), request.url).into(),
+            )
+        });
+
+        self.requests.lock().unwrap().push(request);
+
+        Box::pin(async move { result })
+    }
+}
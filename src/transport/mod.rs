@@ -0,0 +1,334 @@
+//! # Transport
+//! Every request roux makes goes through a `Transport`, which sends a `Request` and returns
+//! the `Response`. Roux ships with transports for both versions of reqwest it supports and
+//! an in-memory `MockTransport` for testing without the network.
+//!
+//! Implement `Transport` to use another HTTP client:
+//! ```
+//! use roux::transport::{BoxFuture, Request, Response, Transport};
+//! use roux::util::RouxError;
+//!
+//! struct Offline;
+//!
+//! impl Transport for Offline {
+//!     fn send(&self, _request: Request) -> BoxFuture<'_, Result<Response, RouxError>> {
+//!         Box::pin(async move { Ok(Response::new(503, "")) })
+//!     }
+//! }
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::util::retry::retry_after;
+use crate::util::{ApiError, RateLimiter, RetryPolicy, RouxError};
+
+mod builder;
+pub use builder::{ClientBuilder, Tls};
+
+/// In-memory transport for tests.
+pub mod mock;
+pub use mock::MockTransport;
+
+/// Transports backed by reqwest.
+#[cfg(any(feature = "tokio_new", feature = "tokio_back_compat"))]
+pub mod reqwest;
+#[cfg(feature = "tokio_back_compat")]
+pub use self::reqwest::ReqwestBackCompatTransport;
+#[cfg(feature = "blocking")]
+pub use self::reqwest::ReqwestBlockingTransport;
+#[cfg(feature = "tokio_new")]
+pub use self::reqwest::ReqwestTransport;
+
+/// A boxed future, as returned by `Transport::send`.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Sends HTTP requests.
+pub trait Transport: Send + Sync {
+    /// Send a request and return the response, whatever its status.
+    fn send(&self, request: Request) -> BoxFuture<'_, Result<Response, RouxError>>;
+}
+
+impl fmt::Debug for dyn Transport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Transport")
+    }
+}
+
+/// The transport used when none is given, picked by the enabled features. It is created
+/// on first use and then shared, so every client reuses the same connection pool.
+#[cfg(feature = "tokio_new")]
+pub fn default_transport() -> Arc<dyn Transport> {
+    static DEFAULT: OnceLock<Arc<dyn Transport>> = OnceLock::new();
+    DEFAULT
+        .get_or_init(|| Arc::new(ReqwestTransport::new()))
+        .clone()
+}
+
+/// The transport used when none is given, picked by the enabled features. It is created
+/// on first use and then shared, so every client reuses the same connection pool.
+#[cfg(all(feature = "tokio_back_compat", not(feature = "tokio_new")))]
+pub fn default_transport() -> Arc<dyn Transport> {
+    static DEFAULT: OnceLock<Arc<dyn Transport>> = OnceLock::new();
+    DEFAULT
+        .get_or_init(|| Arc::new(ReqwestBackCompatTransport::new()))
+        .clone()
+}
+
+#[cfg(not(any(feature = "tokio_new", feature = "tokio_back_compat")))]
+compile_error!("roux needs either the `tokio_new` or `tokio_back_compat` feature");
+
+/// HTTP method.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Method {
+    /// GET
+    Get,
+    /// POST
+    Post,
+}
+
+impl Method {
+    /// Gets the request string for the method
+    pub fn as_str(&self) -> &str {
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+        }
+    }
+}
+
+/// An HTTP request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Request {
+    /// Method
+    pub method: Method,
+    /// Absolute url, including the query string.
+    pub url: String,
+    /// Headers, in the order they were added.
+    pub headers: Vec<(String, String)>,
+    /// Url encoded form body, if any.
+    pub body: Option<String>,
+}
+
+impl Request {
+    /// Create a new `Request` instance.
+    pub fn new(method: Method, url: &str) -> Request {
+        Request {
+            method,
+            url: url.to_owned(),
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    /// Create a new GET `Request`.
+    pub fn get(url: &str) -> Request {
+        Request::new(Method::Get, url)
+    }
+
+    /// Create a new POST `Request`.
+    pub fn post(url: &str) -> Request {
+        Request::new(Method::Post, url)
+    }
+
+    /// Add a header.
+    pub fn header(mut self, name: &str, value: &str) -> Request {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Set an url encoded form as the body. Fails if `form` can't be url encoded, which
+    /// only happens for nested values.
+    pub fn form<T: Serialize + ?Sized>(self, form: &T) -> Result<Request, RouxError> {
+        let body = serde_urlencoded::to_string(form)
+            .map_err(|_| RouxError::InvalidOption("form can't be url encoded"))?;
+
+        let mut request = self.header("content-type", "application/x-www-form-urlencoded");
+        request.body = Some(body);
+        Ok(request)
+    }
+
+    /// Authenticate with a username and password.
+    pub fn basic_auth(self, username: &str, password: &str) -> Request {
+        let credentials = base64::encode(format!("{}:{}", username, password));
+        self.header("authorization", &format!("Basic {}", credentials))
+    }
+
+    /// Authenticate with an access token.
+    pub fn bearer_auth(self, token: &str) -> Request {
+        self.header("authorization", &format!("Bearer {}", token))
+    }
+
+    /// Get the value of a header. Names are compared case insensitively.
+    pub fn header_value(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+/// An HTTP response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Response {
+    /// Status code
+    pub status: u16,
+    /// Headers
+    pub headers: Vec<(String, String)>,
+    /// Body
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// Create a new `Response` instance.
+    pub fn new(status: u16, body: &str) -> Response {
+        Response {
+            status,
+            headers: Vec::new(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    /// Add a header.
+    pub fn header(mut self, name: &str, value: &str) -> Response {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Get the value of a header. Names are compared case insensitively.
+    pub fn header_value(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    /// Whether the status is in the 2xx range.
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// The body as text.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    /// Deserialize the body as JSON.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, RouxError> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+
+    /// Turn a response with a non-success status into an error. Statuses with their own
+    /// `RouxError` variant, such as `404`, use it.
+    pub fn error_for_status(self) -> Result<Response, RouxError> {
+        match self.status {
+            _ if self.is_success() => Ok(self),
+            401 => Err(RouxError::Unauthorized(self)),
+            403 => Err(RouxError::Forbidden(self)),
+            404 => Err(RouxError::NotFound(self)),
+            429 => {
+                let retry_after = retry_after(&self);
+                Err(RouxError::RateLimited(self, retry_after, Vec::new()))
+            }
+            _ => Err(RouxError::Status(self)),
+        }
+    }
+
+    /// Turn errors reported in a `{"json": {"errors": [...]}}` body into an error.
+    pub fn error_for_api(self) -> Result<Response, RouxError> {
+        let errors = ApiError::parse_list(&self.body);
+
+        if errors.is_empty() {
+            Ok(self)
+        } else {
+            Err(RouxError::from_api_errors(errors, self))
+        }
+    }
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| &value[..])
+}
+
+/// Sends a request, waiting for the rate limiter and retrying according to the policy.
+pub(crate) async fn execute(
+    transport: &dyn Transport,
+    rate_limiter: &RateLimiter,
+    retry_policy: &RetryPolicy,
+    write: bool,
+    request: &Request,
+) -> Result<Response, RouxError> {
+    retry_policy
+        .send(write, || async {
+            rate_limiter.wait().await;
+
+            let response = transport.send(request.clone()).await?;
+            rate_limiter.update(&response);
+
+            Ok(response)
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{default_transport, Request, Response};
+    use crate::util::RouxError;
+
+    #[test]
+    fn test_default_transport_is_shared() {
+        assert!(Arc::ptr_eq(&default_transport(), &default_transport()));
+    }
+
+    #[test]
+    fn test_request_form() {
+        let request = Request::post("https://example.com")
+            .form(&[("a", "b c"), ("d", "&")])
+            .unwrap();
+
+        assert_eq!(request.body, Some("a=b+c&d=%26".to_owned()));
+        assert_eq!(
+            request.header_value("Content-Type"),
+            Some("application/x-www-form-urlencoded")
+        );
+    }
+
+    #[test]
+    fn test_request_nested_form() {
+        let request = Request::post("https://example.com").form(&[("a", [1, 2])]);
+        assert!(matches!(request, Err(RouxError::InvalidOption(_))));
+    }
+
+    #[test]
+    fn test_request_basic_auth() {
+        let request = Request::get("https://example.com").basic_auth("id", "secret");
+        assert_eq!(
+            request.header_value("authorization"),
+            Some("Basic aWQ6c2VjcmV0")
+        );
+    }
+
+    #[test]
+    fn test_response() {
+        let response = Response::new(404, "{\"error\": 404}").header("X-Test", "1");
+
+        assert!(!response.is_success());
+        assert_eq!(response.header_value("x-test"), Some("1"));
+        assert!(response.json::<serde_json::Value>().is_ok());
+        assert!(matches!(
+            response.error_for_status(),
+            Err(RouxError::NotFound(ref response)) if response.text() == "{\"error\": 404}"
+        ));
+
+        let response = Response::new(429, "").header("Retry-After", "3");
+        assert!(matches!(
+            response.error_for_status(),
+            Err(RouxError::RateLimited(ref response, Some(delay), _))
+                if delay.as_secs() == 3 && response.header_value("retry-after") == Some("3")
+        ));
+    }
+}
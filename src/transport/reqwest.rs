@@ -0,0 +1,192 @@
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+use super::Tls;
+use super::{BoxFuture, ClientBuilder, Method, Request, Response, Transport};
+use crate::util::RouxError;
+
+/// Applies the settings of a `ClientBuilder` to a reqwest client builder of `$krate`.
+macro_rules! configure {
+    ($krate:ident, $client:expr, $settings:expr) => {{
+        let settings: &ClientBuilder = $settings;
+        let mut client = $client;
+
+        if let Some(timeout) = settings.timeout {
+            client = client.timeout(timeout);
+        }
+
+        if let Some(timeout) = settings.connect_timeout {
+            client = client.connect_timeout(timeout);
+        }
+
+        if let Some(ref proxy) = settings.proxy {
+            client = client.proxy(::$krate::Proxy::all(&proxy[..])?);
+        }
+
+        if let Some(max) = settings.pool_max_idle_per_host {
+            client = client.pool_max_idle_per_host(max);
+        }
+
+        if let Some(timeout) = settings.pool_idle_timeout {
+            client = client.pool_idle_timeout(timeout);
+        }
+
+        match settings.tls {
+            #[cfg(feature = "native-tls")]
+            Tls::NativeTls => client = client.use_native_tls(),
+            #[cfg(feature = "rustls")]
+            Tls::Rustls => client = client.use_rustls_tls(),
+            _ => {}
+        }
+
+        client.build()?
+    }};
+}
+
+/// Turns a `Request` into a request builder of `$client`, which can be any of the reqwest
+/// clients.
+macro_rules! request_builder {
+    ($client:expr, $request:expr) => {{
+        let request: Request = $request;
+        let mut builder = match request.method {
+            Method::Get => $client.get(&request.url[..]),
+            Method::Post => $client.post(&request.url[..]),
+        };
+
+        for (name, value) in request.headers.iter() {
+            builder = builder.header(&name[..], &value[..]);
+        }
+
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+
+        builder
+    }};
+}
+
+/// Reads the status and the headers that are valid strings from a reqwest response.
+macro_rules! response_head {
+    ($response:expr) => {{
+        let response = &$response;
+        let headers: Vec<(String, String)> = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|value| (name.as_str().to_owned(), value.to_owned()))
+            })
+            .collect();
+
+        (response.status().as_u16(), headers)
+    }};
+}
+
+/// Implements `Transport` for a version of reqwest. Both versions share the same API, only
+/// the runtime they need differs.
+macro_rules! reqwest_transport {
+    ($(#[$attr:meta])* $name:ident, $krate:ident) => {
+        $(#[$attr])*
+        #[derive(Clone, Debug, Default)]
+        pub struct $name {
+            client: ::$krate::Client,
+        }
+
+        impl $name {
+            /// Create a new instance with a default client.
+            pub fn new() -> $name {
+                $name::from_client(::$krate::Client::new())
+            }
+
+            /// Create a new instance from a configured client.
+            pub fn from_client(client: ::$krate::Client) -> $name {
+                $name { client }
+            }
+
+            /// Create a new instance with a client configured by `settings`.
+            pub fn from_builder(settings: &ClientBuilder) -> Result<$name, RouxError> {
+                let client = configure!($krate, ::$krate::Client::builder(), settings);
+                Ok($name::from_client(client))
+            }
+        }
+
+        impl Transport for $name {
+            fn send(&self, request: Request) -> BoxFuture<'_, Result<Response, RouxError>> {
+                Box::pin(async move {
+                    let response = request_builder!(self.client, request).send().await?;
+
+                    let (status, headers) = response_head!(response);
+                    let body = response.bytes().await?.to_vec();
+
+                    Ok(Response {
+                        status,
+                        headers,
+                        body,
+                    })
+                })
+            }
+        }
+    };
+}
+
+#[cfg(feature = "tokio_new")]
+reqwest_transport!(
+    /// Transport using reqwest 0.11, for tokio 1.
+    ReqwestTransport,
+    reqwest
+);
+
+#[cfg(feature = "tokio_back_compat")]
+reqwest_transport!(
+    /// Transport using reqwest 0.10, for tokio 0.2.
+    ReqwestBackCompatTransport,
+    reqwest_backcompat
+);
+
+/// Transport using the blocking client of reqwest 0.11. Requests are sent when the future
+/// is first polled, so it only suits executors that may block, like the one used by
+/// `roux::blocking`.
+#[cfg(feature = "blocking")]
+#[derive(Clone, Debug, Default)]
+pub struct ReqwestBlockingTransport {
+    client: ::reqwest::blocking::Client,
+}
+
+#[cfg(feature = "blocking")]
+impl ReqwestBlockingTransport {
+    /// Create a new instance with a default client.
+    pub fn new() -> ReqwestBlockingTransport {
+        ReqwestBlockingTransport::from_client(::reqwest::blocking::Client::new())
+    }
+
+    /// Create a new instance from a configured client.
+    pub fn from_client(client: ::reqwest::blocking::Client) -> ReqwestBlockingTransport {
+        ReqwestBlockingTransport { client }
+    }
+
+    /// Create a new instance with a client configured by `settings`.
+    pub fn from_builder(settings: &ClientBuilder) -> Result<ReqwestBlockingTransport, RouxError> {
+        let client = configure!(reqwest, ::reqwest::blocking::Client::builder(), settings);
+        Ok(ReqwestBlockingTransport::from_client(client))
+    }
+
+    fn send_blocking(&self, request: Request) -> Result<Response, RouxError> {
+        let response = request_builder!(self.client, request).send()?;
+
+        let (status, headers) = response_head!(response);
+        let body = response.bytes()?.to_vec();
+
+        Ok(Response {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl Transport for ReqwestBlockingTransport {
+    fn send(&self, request: Request) -> BoxFuture<'_, Result<Response, RouxError>> {
+        Box::pin(async move { self.send_blocking(request) })
+    }
+}
@@ -12,31 +12,44 @@
 //!     // Now you are able to:
 //!
 //!     // Get overview
-//!     let overview = user.overview().await;
+//!     let overview = user.overview(None).await;
 //!
 //!     // Get submitted posts.
-//!     let submitted = user.submitted().await;
+//!     let submitted = user.submitted(None).await;
 //!
 //!     // Get comments.
-//!     let comments = user.comments().await;
+//!     let comments = user.comments(None).await;
 //! }
 //! ```
 
-extern crate reqwest;
 extern crate serde_json;
 
-use crate::util::RouxError;
-use reqwest::Client;
+use std::sync::Arc;
+
+use futures_util::stream::Stream;
+use serde::de::DeserializeOwned;
+
+use crate::me::{Authenticated, Me};
+use crate::stream::paginate;
+use crate::transport::{self, default_transport, Request, Response, Transport};
+use crate::util::option::push_options;
+use crate::util::{url::Endpoints, FeedOption, RateLimiter, RetryPolicy, RouxError};
 
 pub mod responses;
-use crate::subreddit::responses::{Submissions, SubredditComments};
-use responses::Overview;
+use crate::subreddit::responses::{
+    Submissions, SubmissionsData, SubredditComments, SubredditCommentsData,
+};
+use responses::{Overview, OverviewData};
 
 /// User.
 pub struct User {
     /// User's name.
     pub user: String,
-    client: Client,
+    endpoints: Endpoints,
+    rate_limiter: RateLimiter,
+    retry_policy: RetryPolicy,
+    transport: Arc<dyn Transport>,
+    auth: Option<Arc<Authenticated>>,
 }
 
 impl User {
@@ -44,50 +57,134 @@ impl User {
     pub fn new(user: &str) -> User {
         User {
             user: user.to_owned(),
-            client: Client::new(),
+            endpoints: Endpoints::new(),
+            rate_limiter: RateLimiter::new(),
+            retry_policy: RetryPolicy::new(),
+            transport: default_transport(),
+            auth: None,
+        }
+    }
+
+    /// Sets the endpoints used for requests.
+    pub fn endpoints(mut self, endpoints: Endpoints) -> User {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Sets the rate limiter, to share one budget between several clients.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> User {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// Sets the retry policy.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> User {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets the transport used to send requests.
+    pub fn transport(mut self, transport: Arc<dyn Transport>) -> User {
+        self.transport = transport;
+        self
+    }
+
+    /// Read through `oauth.reddit.com` with the access token of `me`. Requests then use the
+    /// endpoints, rate limiter, retry policy and transport of `me` instead of this user's
+    /// own.
+    pub fn authenticated(mut self, me: &Me) -> User {
+        self.auth = Some(me.auth());
+        self
+    }
+
+    fn url(&self, dest: &str) -> String {
+        match self.auth {
+            Some(ref auth) => auth.config.endpoints.build_oauth(dest),
+            None => self.endpoints.build_url(dest),
+        }
+    }
+
+    async fn get(&self, url: &str) -> Result<Response, RouxError> {
+        if let Some(ref auth) = self.auth {
+            return auth.send(false, Request::get(url)).await;
         }
+
+        transport::execute(
+            &*self.transport,
+            &self.rate_limiter,
+            &self.retry_policy,
+            false,
+            &Request::get(url),
+        )
+        .await?
+        .error_for_status()
+    }
+
+    async fn get_listing<T: DeserializeOwned>(
+        &self,
+        ty: &str,
+        limit: Option<u32>,
+        options: Option<FeedOption>,
+    ) -> Result<T, RouxError> {
+        let url = &mut self.url(&format!("user/{}/{}", self.user, ty));
+        url.push('?');
+        push_options(url, limit, options)?;
+
+        self.get(url).await?.json::<T>()
     }
 
     /// Get user's overview.
-    pub async fn overview(&self) -> Result<Overview, RouxError> {
-        Ok(self
-            .client
-            .get(&format!(
-                "https://www.reddit.com/user/{}/overview/.json",
-                self.user
-            ))
-            .send()
-            .await?
-            .json::<Overview>()
-            .await?)
+    pub async fn overview(&self, options: Option<FeedOption>) -> Result<Overview, RouxError> {
+        self.get_listing("overview", None, options).await
     }
 
     /// Get user's submitted posts.
-    pub async fn submitted(&self) -> Result<Submissions, RouxError> {
-        Ok(self
-            .client
-            .get(&format!(
-                "https://www.reddit.com/user/{}/submitted/.json",
-                self.user
-            ))
-            .send()
-            .await?
-            .json::<Submissions>()
-            .await?)
+    pub async fn submitted(&self, options: Option<FeedOption>) -> Result<Submissions, RouxError> {
+        self.get_listing("submitted", None, options).await
     }
 
     /// Get user's submitted comments.
-    pub async fn comments(&self) -> Result<SubredditComments, RouxError> {
-        Ok(self
-            .client
-            .get(&format!(
-                "https://www.reddit.com/user/{}/comments/.json",
-                self.user
-            ))
-            .send()
-            .await?
-            .json::<SubredditComments>()
-            .await?)
+    pub async fn comments(
+        &self,
+        options: Option<FeedOption>,
+    ) -> Result<SubredditComments, RouxError> {
+        self.get_listing("comments", None, options).await
+    }
+
+    /// Stream user's overview, following `after` from page to page, until `cap` items have
+    /// been returned or the listing ends.
+    pub fn overview_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Stream<Item = Result<OverviewData, RouxError>> + '_ {
+        paginate(options, cap, move |limit, options| {
+            self.get_listing("overview", Some(limit), Some(options))
+        })
+    }
+
+    /// Stream user's submitted posts, following `after` from page to page, until `cap`
+    /// posts have been returned or the listing ends.
+    pub fn submitted_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Stream<Item = Result<SubmissionsData, RouxError>> + '_ {
+        paginate(options, cap, move |limit, options| {
+            self.get_listing("submitted", Some(limit), Some(options))
+        })
+    }
+
+    /// Stream user's submitted comments, following `after` from page to page, until `cap`
+    /// comments have been returned or the listing ends.
+    pub fn comments_paginated(
+        &self,
+        options: Option<FeedOption>,
+        cap: Option<usize>,
+    ) -> impl Stream<Item = Result<SubredditCommentsData, RouxError>> + '_ {
+        paginate(options, cap, move |limit, options| {
+            self.get_listing("comments", Some(limit), Some(options))
+        })
     }
 }
 
@@ -101,15 +198,15 @@ mod tests {
         let user = User::new("beneater");
 
         // Test overview
-        let overview = user.overview().await;
+        let overview = user.overview(None).await;
         assert!(overview.is_ok());
 
         // Test submitted
-        let submitted = user.submitted().await;
+        let submitted = user.submitted(None).await;
         assert!(submitted.is_ok());
 
         // Test comments
-        let comments = user.comments().await;
+        let comments = user.comments(None).await;
         assert!(comments.is_ok());
     }
 }
@@ -1,24 +1,185 @@
 use std::error;
 use std::fmt;
+use std::io;
+use std::time::Duration;
 
-use reqwest;
 use serde_json;
 
+use crate::auth::Scope;
+use crate::transport::Response;
+use crate::util::retry;
+
 /// Error type that occurs when an API request fails for some reason.
 #[derive(Debug)]
 pub enum RouxError {
     /// Occurs when the API has returned a non-success error code.
-    Status(reqwest::Response),
-    /// Occurs if the HTTP response from Reddit was corrupt and
-    /// reqwest could not parse it.
-    Network(reqwest::Error),
+    Status(Response),
+    /// Occurs if the request could not be sent or the response could not be read.
+    Network(Box<dyn error::Error + Send + Sync>),
     /// Occurs if serde could not Deserialize the response.
     Parse(serde_json::Error),
+    /// Occurs when a credential needed for the request has not been set or is invalid.
+    Credentials(&'static str),
+    /// Occurs when an OAuth authorization step fails or is refused.
+    Authorization(String),
+    /// Occurs when the access token was not granted the scope a request needs.
+    MissingScope(Scope),
+    /// Occurs when Reddit reports errors in the body of an otherwise successful response.
+    Api(Vec<ApiError>),
+    /// Occurs when Reddit rejects the credentials or access token.
+    Unauthorized(Response),
+    /// Occurs when the user is not allowed to access a resource, such as a private subreddit.
+    Forbidden(Response),
+    /// Occurs when the resource does not exist.
+    NotFound(Response),
+    /// Occurs when Reddit asks to slow down, with the response and how long to wait if it
+    /// said. A `RATELIMIT` error in the body of a response keeps the errors Reddit reported
+    /// with it.
+    RateLimited(Response, Option<Duration>, Vec<ApiError>),
+    /// Occurs when the options of a request conflict, such as `after` and `before` together.
+    InvalidOption(&'static str),
+}
+
+/// An error Reddit reported in the `json.errors` list of a response, such as
+/// `["SUBREDDIT_NOEXIST", "that subreddit doesn't exist", "sr"]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApiError {
+    /// Error code, e.g. `RATELIMIT`.
+    pub code: String,
+    /// Human readable message.
+    pub message: String,
+    /// The form field the error is about, if any.
+    pub field: Option<String>,
+}
+
+impl ApiError {
+    /// Parses the errors in a `{"json": {"errors": [[code, message, field], ...]}}` body.
+    pub(crate) fn parse_list(body: &[u8]) -> Vec<ApiError> {
+        let value: serde_json::Value = match serde_json::from_slice(body) {
+            Ok(value) => value,
+            Err(_) => return Vec::new(),
+        };
+
+        let errors = match value.pointer("/json/errors").and_then(|e| e.as_array()) {
+            Some(errors) => errors,
+            None => return Vec::new(),
+        };
+
+        errors
+            .iter()
+            .filter_map(|error| {
+                let error = error.as_array()?;
+                let text = |i: usize| error.get(i).and_then(|v| v.as_str()).map(str::to_owned);
+
+                Some(ApiError {
+                    code: text(0)?,
+                    message: text(1).unwrap_or_default(),
+                    field: text(2),
+                })
+            })
+            .collect()
+    }
+
+    /// How long a `RATELIMIT` error asks to wait, from a message like
+    /// "you are doing that too much. try again in 9 minutes."
+    fn retry_after(&self) -> Option<Duration> {
+        let rest = &self.message[self.message.find("try again in ")? + 13..];
+        let mut words = rest.split_whitespace();
+        let amount: u64 = words.next()?.parse().ok()?;
+        let unit = words.next()?;
+
+        if unit.starts_with("millisecond") {
+            Some(Duration::from_millis(amount))
+        } else if unit.starts_with("second") {
+            Some(Duration::from_secs(amount))
+        } else if unit.starts_with("minute") {
+            Some(Duration::from_secs(amount * 60))
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.field {
+            Some(ref field) => write!(f, "{}: {} ({})", self.code, self.message, field),
+            None => write!(f, "{}: {}", self.code, self.message),
+        }
+    }
+}
+
+impl RouxError {
+    /// Whether the request failed before reaching Reddit in a way that may not happen again,
+    /// such as a timeout or a refused connection.
+    pub(crate) fn is_transient(&self) -> bool {
+        let err = match self {
+            RouxError::Network(err) => err,
+            _ => return false,
+        };
+
+        #[cfg(feature = "tokio_new")]
+        {
+            if let Some(err) = err.downcast_ref::<reqwest::Error>() {
+                return err.is_timeout() || err.is_connect();
+            }
+        }
+
+        #[cfg(feature = "tokio_back_compat")]
+        {
+            if let Some(err) = err.downcast_ref::<reqwest_backcompat::Error>() {
+                return err.is_timeout() || err.is_connect();
+            }
+        }
+
+        match err.downcast_ref::<io::Error>() {
+            Some(err) => matches!(
+                err.kind(),
+                io::ErrorKind::TimedOut
+                    | io::ErrorKind::ConnectionRefused
+                    | io::ErrorKind::ConnectionReset
+                    | io::ErrorKind::ConnectionAborted
+            ),
+            None => false,
+        }
+    }
+
+    /// Whether the request may succeed if sent again, as for the failures a `RetryPolicy`
+    /// retries.
+    pub(crate) fn is_retryable(&self) -> bool {
+        match self {
+            RouxError::Status(response) => retry::is_retryable(response.status),
+            RouxError::RateLimited(..) => true,
+            error => error.is_transient(),
+        }
+    }
+
+    /// Turns the errors of a response into a `RouxError`. `RATELIMIT` becomes
+    /// `RouxError::RateLimited` so callers can handle it like a `429`.
+    pub(crate) fn from_api_errors(errors: Vec<ApiError>, response: Response) -> RouxError {
+        let retry_after = errors
+            .iter()
+            .find(|error| error.code == "RATELIMIT")
+            .map(ApiError::retry_after);
+
+        match retry_after {
+            Some(retry_after) => RouxError::RateLimited(response, retry_after, errors),
+            None => RouxError::Api(errors),
+        }
+    }
 }
 
+#[cfg(feature = "tokio_new")]
 impl From<reqwest::Error> for RouxError {
     fn from(e: reqwest::Error) -> Self {
-        RouxError::Network(e)
+        RouxError::Network(Box::new(e))
+    }
+}
+
+#[cfg(feature = "tokio_back_compat")]
+impl From<reqwest_backcompat::Error> for RouxError {
+    fn from(e: reqwest_backcompat::Error) -> Self {
+        RouxError::Network(Box::new(e))
     }
 }
 
@@ -31,19 +192,119 @@ impl From<serde_json::Error> for RouxError {
 impl fmt::Display for RouxError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            RouxError::Status(ref err) => write!(f, "Status error: {}", err.status()),
+            RouxError::Status(ref res) => write!(f, "Status error: {}", res.status),
             RouxError::Network(ref err) => err.fmt(f),
             RouxError::Parse(ref err) => err.fmt(f),
+            RouxError::Credentials(name) => write!(f, "Missing or invalid credential: {}", name),
+            RouxError::Authorization(ref reason) => write!(f, "Authorization error: {}", reason),
+            RouxError::MissingScope(ref scope) => write!(f, "Missing scope: {}", scope),
+            RouxError::Api(ref errors) => {
+                f.write_str("API error: ")?;
+                write_api_errors(f, errors)
+            }
+            RouxError::Unauthorized(_) => f.write_str("Unauthorized"),
+            RouxError::Forbidden(_) => f.write_str("Forbidden"),
+            RouxError::NotFound(_) => f.write_str("Not found"),
+            RouxError::RateLimited(_, delay, ref errors) => {
+                match delay {
+                    Some(delay) => write!(f, "Rate limited, retry in {} seconds", delay.as_secs())?,
+                    None => f.write_str("Rate limited")?,
+                }
+
+                if !errors.is_empty() {
+                    f.write_str(": ")?;
+                    write_api_errors(f, errors)?;
+                }
+
+                Ok(())
+            }
+            RouxError::InvalidOption(reason) => write!(f, "Invalid option: {}", reason),
         }
     }
 }
 
+fn write_api_errors(f: &mut fmt::Formatter, errors: &[ApiError]) -> fmt::Result {
+    for (i, error) in errors.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{}", error)?;
+    }
+
+    Ok(())
+}
+
 impl error::Error for RouxError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match *self {
             RouxError::Status(_) => None,
-            RouxError::Network(ref err) => Some(err),
+            RouxError::Network(ref err) => Some(&**err),
             RouxError::Parse(ref err) => Some(err),
+            RouxError::Credentials(_) => None,
+            RouxError::Authorization(_) => None,
+            RouxError::MissingScope(_) => None,
+            RouxError::Api(_) => None,
+            RouxError::Unauthorized(_) => None,
+            RouxError::Forbidden(_) => None,
+            RouxError::NotFound(_) => None,
+            RouxError::RateLimited(..) => None,
+            RouxError::InvalidOption(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::time::Duration;
+
+    use super::{ApiError, RouxError};
+    use crate::transport::Response;
+
+    #[test]
+    fn test_parse_list() {
+        let body = br#"{"json": {"errors": [["SUBREDDIT_NOEXIST", "that subreddit doesn't exist", "sr"], ["NO_TEXT", "we need something here", null]]}}"#;
+        let errors = ApiError::parse_list(body);
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].code, "SUBREDDIT_NOEXIST");
+        assert_eq!(errors[0].field, Some("sr".to_owned()));
+        assert_eq!(errors[1].field, None);
+
+        assert!(ApiError::parse_list(br#"{"json": {"errors": []}}"#).is_empty());
+        assert!(ApiError::parse_list(b"not json").is_empty());
+    }
+
+    #[test]
+    fn test_is_transient() {
+        let timeout = io::Error::new(io::ErrorKind::TimedOut, "timed out");
+        assert!(RouxError::Network(Box::new(timeout)).is_transient());
+
+        let other = io::Error::new(io::ErrorKind::InvalidData, "invalid data");
+        assert!(!RouxError::Network(Box::new(other)).is_transient());
+        assert!(!RouxError::Network("no route".into()).is_transient());
+        assert!(!RouxError::InvalidOption("limit").is_transient());
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(RouxError::Status(Response::new(503, "")).is_retryable());
+        assert!(RouxError::RateLimited(Response::new(429, ""), None, Vec::new()).is_retryable());
+        assert!(!RouxError::NotFound(Response::new(404, "")).is_retryable());
+        assert!(!RouxError::Credentials("password").is_retryable());
+    }
+
+    #[test]
+    fn test_ratelimit() {
+        let body = r#"{"json": {"errors": [["RATELIMIT", "you are doing that too much. try again in 9 minutes.", "ratelimit"]]}}"#;
+
+        match Response::new(200, body).error_for_api() {
+            Err(RouxError::RateLimited(response, delay, errors)) => {
+                assert_eq!(response.status, 200);
+                assert_eq!(delay, Some(Duration::from_secs(540)));
+                assert_eq!(errors[0].field, Some("ratelimit".to_owned()));
+            }
+            result => panic!("expected a rate limit error, got {:?}", result),
         }
     }
 }
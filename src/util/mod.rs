@@ -4,8 +4,14 @@ pub mod defaults;
 pub mod error;
 /// Url building.
 pub mod url;
-pub use error::RouxError;
+pub use error::{ApiError, RouxError};
 /// Options
 pub mod option;
-pub use option::FeedOption;
 pub use option::TimePeriod;
+pub use option::{CommentOption, CommentSort, FeedOption};
+/// Rate limiting
+pub mod ratelimit;
+pub use ratelimit::RateLimiter;
+/// Retries
+pub mod retry;
+pub use retry::RetryPolicy;
@@ -5,8 +5,11 @@
 //! "next" and "prev" buttons on the site and in combination with count can be used to page
 //! through the listing.
 
+use crate::util::RouxError;
+
 /// Basic feed options
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub struct FeedOption {
     /// `after` and `before` indicate the fullname of an item in the listing to use as the anchor point of the slice.
     pub after: Option<String>,
@@ -14,8 +17,21 @@ pub struct FeedOption {
     pub before: Option<String>,
     /// The number of items already seen in this listing.
     pub count: Option<u32>,
+    /// The maximum number of items to return, up to 100.
+    pub limit: Option<u32>,
     /// What time period to request (only works on some requests, like top)
     pub period: Option<TimePeriod>,
+    /// Show items the user has hidden or that would otherwise be filtered out (`show=all`).
+    pub show_all: bool,
+    /// Expand the subreddit of each item into a `sr_detail` object.
+    pub sr_detail: bool,
+    /// Return text as is instead of escaping `<`, `>` and `&` as HTML entities.
+    pub raw_json: bool,
+    /// Include the categories of saved items.
+    pub include_categories: bool,
+    /// Only show items popular in a region, such as `US` or `GLOBAL` (only works on
+    /// `r/popular`).
+    pub geo_filter: Option<String>,
 }
 
 impl FeedOption {
@@ -25,26 +41,26 @@ impl FeedOption {
             after: None,
             before: None,
             count: None,
+            limit: None,
             period: None,
+            show_all: false,
+            sr_detail: false,
+            raw_json: false,
+            include_categories: false,
+            geo_filter: None,
         }
     }
 
-    /// Set after param.
+    /// Set after param. Setting both `after` and `before` makes the request fail with
+    /// `RouxError::InvalidOption`.
     pub fn after(mut self, ty: &str) -> FeedOption {
-        if self.before.is_some() {
-            panic!("Cannot have an after and before param at the same time");
-        }
-
         self.after = Some(ty.to_owned());
         self
     }
 
-    /// Set before param.
+    /// Set before param. Setting both `after` and `before` makes the request fail with
+    /// `RouxError::InvalidOption`.
     pub fn before(mut self, ty: &str) -> FeedOption {
-        if self.after.is_some() {
-            panic!("Cannot have an after and before param at the same time");
-        }
-
         self.before = Some(ty.to_owned());
         self
     }
@@ -55,14 +71,62 @@ impl FeedOption {
         self
     }
 
+    /// Set limit param, for methods that don't take a limit themselves. Methods that do
+    /// fail with `RouxError::InvalidOption` if this is set too, and paginated streams use
+    /// it as the page size.
+    pub fn limit(mut self, limit: u32) -> FeedOption {
+        self.limit = Some(limit);
+        self
+    }
+
     /// Set period
     pub fn period(mut self, period: TimePeriod) -> FeedOption {
         self.period = Some(period);
         self
     }
 
-    /// build a url from FeedOption
-    pub fn build_url(self, url: &mut String) {
+    /// Set show param to `all`.
+    pub fn show_all(mut self, show_all: bool) -> FeedOption {
+        self.show_all = show_all;
+        self
+    }
+
+    /// Set sr_detail param.
+    pub fn sr_detail(mut self, sr_detail: bool) -> FeedOption {
+        self.sr_detail = sr_detail;
+        self
+    }
+
+    /// Set raw_json param.
+    pub fn raw_json(mut self, raw_json: bool) -> FeedOption {
+        self.raw_json = raw_json;
+        self
+    }
+
+    /// Set include_categories param.
+    pub fn include_categories(mut self, include_categories: bool) -> FeedOption {
+        self.include_categories = include_categories;
+        self
+    }
+
+    /// Set geo filter, the `g` param.
+    pub fn geo_filter(mut self, region: &str) -> FeedOption {
+        self.geo_filter = Some(region.to_owned());
+        self
+    }
+
+    /// build a url from FeedOption, failing if the options conflict.
+    pub fn build_url(self, url: &mut String) -> Result<(), RouxError> {
+        if self.after.is_some() && self.before.is_some() {
+            return Err(RouxError::InvalidOption(
+                "cannot have an after and before param at the same time",
+            ));
+        }
+
+        if let Some(limit) = self.limit {
+            url.push_str(&format!("&limit={}", limit));
+        }
+
         if let Some(after) = self.after {
             url.push_str(&format!("&after={}", after));
         } else if let Some(before) = self.before {
@@ -76,6 +140,68 @@ impl FeedOption {
         if let Some(period) = self.period {
             url.push_str(&format!("&t={}", period.get_string_for_period()));
         }
+
+        if let Some(region) = self.geo_filter {
+            url.push_str(&format!("&g={}", region));
+        }
+
+        if self.show_all {
+            url.push_str("&show=all");
+        }
+
+        if self.sr_detail {
+            url.push_str("&sr_detail=true");
+        }
+
+        if self.raw_json {
+            url.push_str("&raw_json=1");
+        }
+
+        if self.include_categories {
+            url.push_str("&include_categories=true");
+        }
+
+        Ok(())
+    }
+}
+
+/// Appends `options` to the query string of `url`, failing if both `limit` and the limit
+/// in `options` are set.
+pub(crate) fn push_options(
+    url: &mut String,
+    limit: Option<u32>,
+    options: Option<FeedOption>,
+) -> Result<(), RouxError> {
+    let mut options = options.unwrap_or_default();
+
+    if limit.is_some() {
+        if options.limit.is_some() {
+            return Err(RouxError::InvalidOption(
+                "cannot pass a limit and set it in FeedOption at the same time",
+            ));
+        }
+        options.limit = limit;
+    }
+
+    let mut query = String::new();
+    options.build_url(&mut query)?;
+    push_query(url, &query);
+
+    Ok(())
+}
+
+/// Appends `options` to the query string of `url`.
+pub(crate) fn push_comment_options(url: &mut String, options: Option<CommentOption>) {
+    let mut query = String::new();
+    options.unwrap_or_default().build_url(&mut query);
+    push_query(url, &query);
+}
+
+fn push_query(url: &mut String, query: &str) {
+    if url.ends_with('?') {
+        url.push_str(query.trim_start_matches('&'));
+    } else {
+        url.push_str(query);
     }
 }
 
@@ -116,9 +242,142 @@ impl TimePeriod {
     }
 }
 
+/// Options for the comments of a submission.
+#[derive(Clone, Debug, Default)]
+pub struct CommentOption {
+    /// How many levels of replies to return.
+    pub depth: Option<u32>,
+    /// The maximum number of comments to return.
+    pub limit: Option<u32>,
+    /// How to sort the comments.
+    pub sort: Option<CommentSort>,
+    /// The id of a comment to focus on, showing only it, its replies and its parents.
+    pub comment: Option<String>,
+    /// How many parents of the focused comment to show, from 0 to 8.
+    pub context: Option<u32>,
+    /// Whether to include "load more comments" stubs.
+    pub showmore: Option<bool>,
+    /// Whether to return the comments as a tree, or as a flat list when `false`.
+    pub threaded: Option<bool>,
+}
+
+impl CommentOption {
+    /// Create a new `CommentOption` instance.
+    pub fn new() -> CommentOption {
+        CommentOption::default()
+    }
+
+    /// Set depth.
+    pub fn depth(mut self, depth: u32) -> CommentOption {
+        self.depth = Some(depth);
+        self
+    }
+
+    /// Set limit.
+    pub fn limit(mut self, limit: u32) -> CommentOption {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Set sort
+    pub fn sort(mut self, sort: CommentSort) -> CommentOption {
+        self.sort = Some(sort);
+        self
+    }
+
+    /// Focus on the comment with the id `comment`, with or without the `t1_` prefix.
+    pub fn comment(mut self, comment: &str) -> CommentOption {
+        self.comment = Some(comment.trim_start_matches("t1_").to_owned());
+        self
+    }
+
+    /// Set context param.
+    pub fn context(mut self, context: u32) -> CommentOption {
+        self.context = Some(context);
+        self
+    }
+
+    /// Set showmore param.
+    pub fn showmore(mut self, showmore: bool) -> CommentOption {
+        self.showmore = Some(showmore);
+        self
+    }
+
+    /// Set threaded param.
+    pub fn threaded(mut self, threaded: bool) -> CommentOption {
+        self.threaded = Some(threaded);
+        self
+    }
+
+    /// build a url from CommentOption
+    pub fn build_url(self, url: &mut String) {
+        if let Some(depth) = self.depth {
+            url.push_str(&format!("&depth={}", depth));
+        }
+
+        if let Some(limit) = self.limit {
+            url.push_str(&format!("&limit={}", limit));
+        }
+
+        if let Some(sort) = self.sort {
+            url.push_str(&format!("&sort={}", sort.as_str()));
+        }
+
+        if let Some(comment) = self.comment {
+            url.push_str(&format!("&comment={}", comment));
+        }
+
+        if let Some(context) = self.context {
+            url.push_str(&format!("&context={}", context));
+        }
+
+        if let Some(showmore) = self.showmore {
+            url.push_str(&format!("&showmore={}", showmore));
+        }
+
+        if let Some(threaded) = self.threaded {
+            url.push_str(&format!("&threaded={}", threaded));
+        }
+    }
+}
+
+/// How to sort the comments of a submission.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CommentSort {
+    /// Best comments first, the default
+    Confidence,
+    /// Highest score first
+    Top,
+    /// Newest first
+    New,
+    /// Most controversial first
+    Controversial,
+    /// Oldest first
+    Old,
+    /// Answers of the submission author first
+    Qa,
+}
+
+impl CommentSort {
+    /// Gets the request string for the sort
+    pub fn as_str(&self) -> &str {
+        match self {
+            CommentSort::Confidence => "confidence",
+            CommentSort::Top => "top",
+            CommentSort::New => "new",
+            CommentSort::Controversial => "controversial",
+            CommentSort::Old => "old",
+            CommentSort::Qa => "qa",
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::FeedOption;
+    use super::{
+        push_comment_options, push_options, CommentOption, CommentSort, FeedOption, TimePeriod,
+    };
+    use crate::util::RouxError;
 
     #[test]
     fn test_build_url_after() {
@@ -126,7 +385,7 @@ mod tests {
         let options = FeedOption::new().after(after);
 
         let url = &mut String::from("");
-        options.build_url(url);
+        options.build_url(url).unwrap();
 
         assert!(*url == format!("&after={}", after))
     }
@@ -137,7 +396,7 @@ mod tests {
         let options = FeedOption::new().before(before);
 
         let url = &mut String::from("");
-        options.build_url(url);
+        options.build_url(url).unwrap();
 
         assert!(*url == format!("&before={}", before))
     }
@@ -148,8 +407,86 @@ mod tests {
         let options = FeedOption::new().count(count);
 
         let url = &mut String::from("");
-        options.build_url(url);
+        options.build_url(url).unwrap();
 
         assert!(*url == format!("&count={}", count))
     }
+
+    #[test]
+    fn test_build_url_conflict() {
+        let options = FeedOption::new().after("t3_a").before("t3_b");
+
+        let url = &mut String::from("");
+        assert!(matches!(
+            options.build_url(url),
+            Err(RouxError::InvalidOption(_))
+        ));
+    }
+
+    #[test]
+    fn test_build_url_all() {
+        let options = FeedOption::new()
+            .limit(10)
+            .period(TimePeriod::ThisWeek)
+            .show_all(true)
+            .sr_detail(true)
+            .raw_json(true)
+            .include_categories(true);
+
+        let url = &mut String::from("");
+        options.build_url(url).unwrap();
+
+        assert_eq!(
+            url,
+            "&limit=10&t=week&show=all&sr_detail=true&raw_json=1&include_categories=true"
+        );
+    }
+
+    #[test]
+    fn test_push_options() {
+        let url = &mut String::from("hot.json?");
+        push_options(url, Some(25), Some(FeedOption::new().after("t3_a"))).unwrap();
+        assert_eq!(url, "hot.json?limit=25&after=t3_a");
+
+        let url = &mut String::from("hot.json?");
+        assert!(matches!(
+            push_options(url, Some(25), Some(FeedOption::new().limit(10))),
+            Err(RouxError::InvalidOption(_))
+        ));
+
+        let url = &mut String::from("search.json?q=rust");
+        push_options(url, None, Some(FeedOption::new().limit(10))).unwrap();
+        assert_eq!(url, "search.json?q=rust&limit=10");
+    }
+
+    #[test]
+    fn test_build_comment_url() {
+        let options = CommentOption::new()
+            .depth(2)
+            .limit(10)
+            .sort(CommentSort::Old)
+            .comment("t1_abc")
+            .context(3)
+            .showmore(false)
+            .threaded(true);
+
+        let url = &mut String::from("");
+        options.build_url(url);
+
+        assert_eq!(
+            url,
+            "&depth=2&limit=10&sort=old&comment=abc&context=3&showmore=false&threaded=true"
+        );
+    }
+
+    #[test]
+    fn test_push_comment_options() {
+        let url = &mut String::from("comments/abc.json?");
+        push_comment_options(url, Some(CommentOption::new().limit(25).comment("def")));
+        assert_eq!(url, "comments/abc.json?limit=25&comment=def");
+
+        let url = &mut String::from("comments/abc.json?");
+        push_comment_options(url, None);
+        assert_eq!(url, "comments/abc.json?");
+    }
 }
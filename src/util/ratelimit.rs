@@ -0,0 +1,234 @@
+//! # Rate limiting
+//! Reddit reports how much of the request budget is left with the `X-Ratelimit-Remaining`
+//! and `X-Ratelimit-Reset` headers. A `RateLimiter` keeps track of them and delays requests
+//! once the budget is used up until the window resets.
+//!
+//! Clones of a `RateLimiter` share the same budget, so pass one limiter to every client
+//! that uses the same credentials.
+//!
+//! ```
+//! use roux::{Reddit, Subreddit};
+//! use roux::util::RateLimiter;
+//!
+//! let limiter = RateLimiter::new();
+//!
+//! let reddit = Reddit::new("USER_AGENT", "CLIENT_ID", "CLIENT_SECRET")
+//!     .rate_limiter(limiter.clone());
+//! let subreddit = Subreddit::new("rust").rate_limiter(limiter.clone());
+//! ```
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures_timer::Delay;
+
+use crate::transport::Response;
+
+/// Header with the number of requests left in the current window.
+const REMAINING: &str = "x-ratelimit-remaining";
+/// Header with the number of seconds until the window resets.
+const RESET: &str = "x-ratelimit-reset";
+/// Header with the number of requests used in the current window.
+const USED: &str = "x-ratelimit-used";
+/// The reset header is in whole seconds, so responses from the same window can report
+/// reset times up to a second apart.
+const RESET_SLACK: Duration = Duration::from_secs(1);
+
+/// Tracks Reddit's rate limit headers and delays requests when the budget is exhausted.
+#[derive(Clone, Debug, Default)]
+pub struct RateLimiter {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    remaining: Option<f64>,
+    used: Option<u64>,
+    reset_at: Option<Instant>,
+}
+
+impl RateLimiter {
+    /// Create a new `RateLimiter` instance.
+    pub fn new() -> RateLimiter {
+        RateLimiter::default()
+    }
+
+    /// Requests left in the current window, if known.
+    pub fn remaining(&self) -> Option<f64> {
+        self.state.lock().unwrap().remaining
+    }
+
+    /// Requests used in the current window, if known.
+    pub fn used(&self) -> Option<u64> {
+        self.state.lock().unwrap().used
+    }
+
+    /// Time until the current window resets, if known.
+    pub fn reset_in(&self) -> Option<Duration> {
+        self.state
+            .lock()
+            .unwrap()
+            .reset_at
+            .map(|reset_at| reset_at.saturating_duration_since(Instant::now()))
+    }
+
+    /// Waits until a request can be made. Other requests may have used up the next
+    /// window by the time the wait is over, so the budget is checked again after each wait.
+    pub(crate) async fn wait(&self) {
+        while let Some(delay) = self.reserve(Instant::now()) {
+            Delay::new(delay).await;
+        }
+    }
+
+    /// Takes a request from the budget, or returns how long to wait if there is nothing
+    /// left.
+    fn reserve(&self, now: Instant) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap();
+        let exhausted = matches!(state.remaining, Some(remaining) if remaining < 1.0);
+
+        match state.reset_at {
+            Some(reset_at) if reset_at <= now => {
+                *state = State::default();
+                None
+            }
+            Some(reset_at) if exhausted => Some(reset_at - now),
+            _ => {
+                if let Some(ref mut remaining) = state.remaining {
+                    *remaining -= 1.0;
+                }
+                None
+            }
+        }
+    }
+
+    /// Updates the budget from the headers of a response.
+    pub(crate) fn update(&self, response: &Response) {
+        let header = |name| response.header_value(name).map(|value| value.trim());
+
+        self.update_values(
+            header(REMAINING).and_then(|v| v.parse().ok()),
+            header(USED).and_then(|v| v.parse().ok()),
+            header(RESET).and_then(|v| v.parse().ok()),
+            Instant::now(),
+        );
+    }
+
+    fn update_values(
+        &self,
+        remaining: Option<f64>,
+        used: Option<u64>,
+        reset: Option<u64>,
+        now: Instant,
+    ) {
+        if remaining.is_none() && reset.is_none() {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let reset_at = reset.map(|secs| now + Duration::from_secs(secs));
+
+        // Responses can arrive out of order, so an older one shouldn't give back budget
+        // that has since been used.
+        let same_window = match (state.reset_at, reset_at) {
+            (Some(current), Some(new)) => current > now && new <= current + RESET_SLACK,
+            _ => false,
+        };
+        let raises = matches!(
+            (state.remaining, remaining),
+            (Some(current), Some(new)) if new > current
+        );
+        if same_window && raises {
+            return;
+        }
+
+        state.remaining = remaining;
+        state.used = used;
+        state.reset_at = reset_at;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::RateLimiter;
+    use crate::transport::Response;
+
+    #[test]
+    fn test_unknown_budget() {
+        let limiter = RateLimiter::new();
+        assert_eq!(limiter.reserve(Instant::now()), None);
+        assert_eq!(limiter.remaining(), None);
+    }
+
+    #[test]
+    fn test_reserve_until_exhausted() {
+        let limiter = RateLimiter::new();
+        let now = Instant::now();
+        limiter.update_values(Some(2.0), Some(598), Some(10), now);
+
+        assert_eq!(limiter.reserve(now), None);
+        assert_eq!(limiter.reserve(now), None);
+        assert_eq!(limiter.reserve(now), Some(Duration::from_secs(10)));
+        assert_eq!(
+            limiter.reserve(now + Duration::from_secs(4)),
+            Some(Duration::from_secs(6))
+        );
+    }
+
+    #[test]
+    fn test_window_reset() {
+        let limiter = RateLimiter::new();
+        let now = Instant::now();
+        limiter.update_values(Some(0.0), Some(600), Some(10), now);
+
+        assert_eq!(limiter.reserve(now + Duration::from_secs(10)), None);
+        assert_eq!(limiter.remaining(), None);
+    }
+
+    #[test]
+    fn test_ignores_responses_without_headers() {
+        let limiter = RateLimiter::new();
+        let now = Instant::now();
+        limiter.update_values(Some(5.0), Some(595), Some(10), now);
+        limiter.update_values(None, None, None, now);
+
+        assert_eq!(limiter.remaining(), Some(5.0));
+    }
+
+    #[test]
+    fn test_ignores_out_of_order_responses() {
+        let limiter = RateLimiter::new();
+        let now = Instant::now();
+        limiter.update_values(Some(5.0), Some(595), Some(10), now);
+        limiter.update_values(Some(7.0), Some(593), Some(10), now);
+        assert_eq!(limiter.remaining(), Some(5.0));
+        assert_eq!(limiter.used(), Some(595));
+
+        limiter.update_values(Some(4.0), Some(596), Some(10), now);
+        assert_eq!(limiter.remaining(), Some(4.0));
+
+        // A new window gives back the budget.
+        limiter.update_values(
+            Some(600.0),
+            Some(0),
+            Some(600),
+            now + Duration::from_secs(10),
+        );
+        assert_eq!(limiter.remaining(), Some(600.0));
+    }
+
+    #[test]
+    fn test_update_from_response() {
+        let limiter = RateLimiter::new();
+        let response = Response::new(200, "")
+            .header("X-Ratelimit-Remaining", "12.0")
+            .header("X-Ratelimit-Used", "588")
+            .header("X-Ratelimit-Reset", "300");
+        limiter.update(&response);
+
+        assert_eq!(limiter.remaining(), Some(12.0));
+        assert_eq!(limiter.used(), Some(588));
+        assert!(limiter.reset_in().unwrap() <= Duration::from_secs(300));
+    }
+}
@@ -0,0 +1,224 @@
+//! # Retries
+//! Reddit regularly answers with `429 Too Many Requests` or a `5xx` error during outages.
+//! A `RetryPolicy` retries those responses, and failed connections, with exponential
+//! backoff and jitter. If Reddit sends a `Retry-After` header it is used instead, unless it
+//! is longer than the maximum delay, in which case the response is returned right away.
+//!
+//! Reads are always safe to retry. Writes, such as submitting a post, are only retried if
+//! `retry_writes` is set, because a request that timed out may still have gone through.
+//!
+//! ```
+//! use std::time::Duration;
+//! use roux::Subreddit;
+//! use roux::util::RetryPolicy;
+//!
+//! let policy = RetryPolicy::new()
+//!     .max_attempts(5)
+//!     .base_delay(Duration::from_millis(500));
+//!
+//! let subreddit = Subreddit::new("rust").retry_policy(policy);
+//! ```
+
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use futures_timer::Delay;
+
+use crate::transport::Response;
+use crate::util::RouxError;
+
+/// How and when to retry failed requests.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    retry_writes: bool,
+}
+
+impl RetryPolicy {
+    /// Create a new `RetryPolicy` instance that makes up to 3 attempts at reads, waiting
+    /// about 1 second and then 2 seconds between them.
+    pub fn new() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+            retry_writes: false,
+        }
+    }
+
+    /// A policy that never retries.
+    pub fn never() -> RetryPolicy {
+        RetryPolicy::new().max_attempts(1)
+    }
+
+    /// Set the maximum number of attempts, including the first one.
+    pub fn max_attempts(mut self, max_attempts: u32) -> RetryPolicy {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the delay before the first retry. Each retry after that waits twice as long.
+    pub fn base_delay(mut self, base_delay: Duration) -> RetryPolicy {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Set the longest delay between two attempts. Responses asking to wait longer than this
+    /// with `Retry-After` are not retried.
+    pub fn max_delay(mut self, max_delay: Duration) -> RetryPolicy {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Set whether writes are retried too.
+    pub fn retry_writes(mut self, retry_writes: bool) -> RetryPolicy {
+        self.retry_writes = retry_writes;
+        self
+    }
+
+    /// How long to wait after a failed `attempt`, counting from 1. The delay is picked at
+    /// random between half and all of the backoff so clients don't retry in lockstep.
+    fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(retry_after) = retry_after {
+            return retry_after;
+        }
+
+        let backoff = self
+            .base_delay
+            .checked_mul(1 << (attempt - 1).min(16))
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+
+        backoff / 2 + backoff.mul_f64(random() / 2.0)
+    }
+
+    /// Sends a request with `send`, retrying it according to the policy.
+    pub(crate) async fn send<F, Fut>(&self, write: bool, send: F) -> Result<Response, RouxError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<Response, RouxError>>,
+    {
+        let max_attempts = if write && !self.retry_writes {
+            1
+        } else {
+            self.max_attempts
+        };
+
+        let mut attempt = 1;
+
+        loop {
+            let result = send().await;
+
+            let retry_after = match result {
+                Ok(ref response) if is_retryable(response.status) => retry_after(response),
+                Err(ref error) if error.is_transient() => None,
+                _ => return result,
+            };
+
+            if attempt >= max_attempts || matches!(retry_after, Some(d) if d > self.max_delay) {
+                return result;
+            }
+
+            Delay::new(self.delay(attempt, retry_after)).await;
+            attempt += 1;
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(crate) fn is_retryable(status: u16) -> bool {
+    status == 429 || (500..600).contains(&status)
+}
+
+/// Reads the `Retry-After` header, which Reddit sends in seconds.
+pub(crate) fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .header_value("retry-after")
+        .and_then(|value| value.trim().parse().ok())
+        .map(Duration::from_secs)
+}
+
+/// A random number between 0 and 1.
+fn random() -> f64 {
+    let bits = RandomState::new().build_hasher().finish() >> 11;
+    bits as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::time::Duration;
+
+    use super::{is_retryable, retry_after, RetryPolicy};
+    use crate::transport::Response;
+
+    #[test]
+    fn test_delay_backoff() {
+        let policy = RetryPolicy::new()
+            .base_delay(Duration::from_secs(1))
+            .max_delay(Duration::from_secs(5));
+
+        for _ in 0..100 {
+            let first = policy.delay(1, None);
+            assert!(first >= Duration::from_millis(500) && first <= Duration::from_secs(1));
+
+            let second = policy.delay(2, None);
+            assert!(second >= Duration::from_secs(1) && second <= Duration::from_secs(2));
+
+            let capped = policy.delay(10, None);
+            assert!(capped >= Duration::from_millis(2500) && capped <= Duration::from_secs(5));
+        }
+    }
+
+    #[test]
+    fn test_delay_retry_after() {
+        let policy = RetryPolicy::new();
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(7))),
+            Duration::from_secs(7)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_over_max_delay() {
+        let policy = RetryPolicy::new().max_delay(Duration::from_secs(5));
+        let attempts = Cell::new(0);
+
+        let response = policy
+            .send(false, || {
+                attempts.set(attempts.get() + 1);
+                async { Ok(Response::new(429, "").header("Retry-After", "60")) }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(attempts.get(), 1);
+        assert_eq!(retry_after(&response), Some(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_retry_after_header() {
+        let response = Response::new(429, "");
+        assert_eq!(retry_after(&response), None);
+
+        let response = response.header("Retry-After", "12");
+        assert_eq!(retry_after(&response), Some(Duration::from_secs(12)));
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(is_retryable(429));
+        assert!(is_retryable(502));
+        assert!(!is_retryable(404));
+        assert!(!is_retryable(200));
+    }
+}
@@ -1,9 +1,137 @@
+//! # Endpoints
+//! Every request roux makes is built from one of three hosts: the read only `www` host,
+//! the `oauth` host used with a bearer token and the `auth` host that hands out and
+//! revokes tokens. `Endpoints` holds all three so the whole crate can be pointed somewhere
+//! other than reddit.com, such as a local mock server or a staging proxy.
+//!
+//! ```
+//! use roux::util::url::Endpoints;
+//!
+//! let endpoints = Endpoints::new()
+//!     .www("http://localhost:8080")
+//!     .oauth("http://localhost:8080")
+//!     .auth("http://localhost:8080");
+//!
+//! assert_eq!(endpoints.build_url("r/rust/hot"), "http://localhost:8080/r/rust/hot/.json");
+//! ```
+
+/// Default host for read only access.
+pub const WWW_HOST: &str = "https://www.reddit.com";
+/// Default host for OAuth access.
+pub const OAUTH_HOST: &str = "https://oauth.reddit.com";
+/// Default host for requesting and revoking tokens.
+pub const AUTH_HOST: &str = "https://www.reddit.com";
+
+/// Base URLs used to build every request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Endpoints {
+    /// Base URL for read only access, e.g. `https://www.reddit.com`.
+    pub www: String,
+    /// Base URL for OAuth access, e.g. `https://oauth.reddit.com`.
+    pub oauth: String,
+    /// Base URL for the token endpoints, e.g. `https://www.reddit.com`.
+    pub auth: String,
+}
+
+impl Endpoints {
+    /// Create a new `Endpoints` instance pointing at reddit.com.
+    pub fn new() -> Endpoints {
+        Endpoints {
+            www: WWW_HOST.to_owned(),
+            oauth: OAUTH_HOST.to_owned(),
+            auth: AUTH_HOST.to_owned(),
+        }
+    }
+
+    /// Set the read only base URL.
+    pub fn www(mut self, base: &str) -> Endpoints {
+        self.www = trim_base(base);
+        self
+    }
+
+    /// Set the OAuth base URL.
+    pub fn oauth(mut self, base: &str) -> Endpoints {
+        self.oauth = trim_base(base);
+        self
+    }
+
+    /// Set the token endpoint base URL.
+    pub fn auth(mut self, base: &str) -> Endpoints {
+        self.auth = trim_base(base);
+        self
+    }
+
+    /// Builds a url for read only Reddit access.
+    pub fn build_url(&self, dest: &str) -> String {
+        format!("{}/{}/.json", self.www, dest)
+    }
+
+    /// Builds a url for OAuth Reddit access.
+    pub fn build_oauth(&self, dest: &str) -> String {
+        format!("{}/{}/.json", self.oauth, dest)
+    }
+
+    /// Builds a url for the token endpoints.
+    pub fn build_auth(&self, dest: &str) -> String {
+        format!("{}/{}", self.auth, dest)
+    }
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn trim_base(base: &str) -> String {
+    base.trim_end_matches('/').to_owned()
+}
+
 /// Builds a url for read only Reddit access.
 pub fn build_url(dest: &str) -> String {
-    format!("https://www.reddit.com/{}/.json", dest)
+    Endpoints::new().build_url(dest)
 }
 
 /// Builds a url for OAuth Reddit access.
 pub fn build_oauth(dest: &str) -> String {
-    format!("https://oauth.reddit.com/{}/.json", dest)
+    Endpoints::new().build_oauth(dest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_oauth, build_url, Endpoints};
+
+    #[test]
+    fn test_default_hosts() {
+        assert_eq!(build_url("r/rust"), "https://www.reddit.com/r/rust/.json");
+        assert_eq!(
+            build_oauth("api/v1/me"),
+            "https://oauth.reddit.com/api/v1/me/.json"
+        );
+        assert_eq!(
+            Endpoints::new().build_auth("api/v1/access_token"),
+            "https://www.reddit.com/api/v1/access_token"
+        );
+    }
+
+    #[test]
+    fn test_custom_hosts() {
+        let endpoints = Endpoints::new()
+            .www("http://127.0.0.1:1234/")
+            .oauth("http://127.0.0.1:1235")
+            .auth("http://127.0.0.1:1236");
+
+        assert_eq!(
+            endpoints.build_url("r/rust"),
+            "http://127.0.0.1:1234/r/rust/.json"
+        );
+        assert_eq!(
+            endpoints.build_oauth("api/v1/me"),
+            "http://127.0.0.1:1235/api/v1/me/.json"
+        );
+        assert_eq!(
+            endpoints.build_auth("api/v1/revoke_token"),
+            "http://127.0.0.1:1236/api/v1/revoke_token"
+        );
+    }
 }
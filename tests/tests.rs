@@ -8,7 +8,6 @@ extern crate tokio;
 mod tests {
 
     use roux::Reddit;
-    use tokio;
 
     static USER_AGENT: &str = "macos:roux:v0.3.0 (by /u/beanpup_py)";
 
@@ -19,7 +18,7 @@ mod tests {
         let username = dotenv::var("USERNAME").unwrap();
         let password = dotenv::var("PASSWORD").unwrap();
 
-        let client = Reddit::new(&USER_AGENT, &client_id, &client_secret)
+        let client = Reddit::new(USER_AGENT, &client_id, &client_secret)
             .username(&username)
             .password(&password)
             .login()